@@ -0,0 +1,341 @@
+use anyhow::{Context, Result};
+use colored::Colorize;
+use falkordb::FalkorSyncClient;
+use std::any::Any;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::num::NonZeroU16;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::client::{FalkorCli, POOL_LIMIT};
+use crate::params;
+
+/// Settings for the `bench` subcommand.
+pub struct BenchOptions {
+    pub graph: String,
+    pub query: String,
+    pub requests: u64,
+    pub concurrency: NonZeroU16,
+    pub duration: Option<Duration>,
+    pub params_file: Option<String>,
+    pub readonly: bool,
+}
+
+/// Timing of one successful request.
+#[derive(Clone, Copy, Debug)]
+struct Sample {
+    /// Client round-trip time in milliseconds
+    client_ms: f64,
+    /// Server-reported internal execution time in milliseconds
+    server_ms: Option<f64>,
+}
+
+const STAT_NAMES: [&str; 5] = ["mean", "p50", "p90", "p99", "max"];
+
+/// Distinct error messages kept for the report; later ones are only counted.
+pub const MAX_ERROR_MESSAGES: usize = 5;
+
+/// Failed requests, with the first few distinct error messages.
+#[derive(Debug, Default)]
+pub struct ErrorLog {
+    pub count: u64,
+    pub messages: Vec<String>,
+}
+
+impl ErrorLog {
+    pub fn record(&mut self, message: String) {
+        self.count += 1;
+        self.keep(message);
+    }
+
+    /// Adds the failures seen by another worker.
+    pub fn merge(&mut self, other: Self) {
+        self.count += other.count;
+        for message in other.messages {
+            self.keep(message);
+        }
+    }
+
+    fn keep(&mut self, message: String) {
+        if self.messages.len() < MAX_ERROR_MESSAGES && !self.messages.contains(&message) {
+            self.messages.push(message);
+        }
+    }
+}
+
+/// Latency distribution of a set of samples, in milliseconds.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LatencySummary {
+    pub mean: f64,
+    pub p50: f64,
+    pub p90: f64,
+    pub p99: f64,
+    pub max: f64,
+}
+
+impl LatencySummary {
+    /// Summarizes samples using nearest-rank percentiles. Returns `None` when
+    /// there are no samples.
+    pub fn from_samples(samples: &mut [f64]) -> Option<Self> {
+        if samples.is_empty() {
+            return None;
+        }
+        samples.sort_by(f64::total_cmp);
+
+        #[allow(
+            clippy::cast_precision_loss,
+            clippy::cast_possible_truncation,
+            clippy::cast_sign_loss
+        )]
+        let percentile = |p: f64| {
+            let rank = (p / 100.0 * samples.len() as f64).ceil() as usize;
+            samples[rank.clamp(1, samples.len()) - 1]
+        };
+
+        #[allow(clippy::cast_precision_loss)]
+        let mean = samples.iter().sum::<f64>() / samples.len() as f64;
+
+        Some(Self {
+            mean,
+            p50: percentile(50.0),
+            p90: percentile(90.0),
+            p99: percentile(99.0),
+            max: samples[samples.len() - 1],
+        })
+    }
+
+    /// Statistics in the order of [`STAT_NAMES`].
+    const fn values(self) -> [f64; 5] {
+        [self.mean, self.p50, self.p90, self.p99, self.max]
+    }
+
+    pub fn to_json(self) -> serde_json::Value {
+        let stats: serde_json::Map<String, serde_json::Value> = STAT_NAMES
+            .iter()
+            .zip(self.values())
+            .map(|(name, value)| ((*name).to_string(), serde_json::json!(value)))
+            .collect();
+        serde_json::Value::Object(stats)
+    }
+}
+
+/// Reads a params file with one JSON object per line; blank lines are skipped.
+pub fn parse_params_file(contents: &str) -> Result<Vec<BTreeMap<String, serde_json::Value>>> {
+    contents
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            params::parse_params_object(line).with_context(|| format!("Line {}", i + 1))
+        })
+        .collect()
+}
+
+impl FalkorCli {
+    pub fn run_bench(&self, options: &BenchOptions) -> Result<()> {
+        let param_sets = match &options.params_file {
+            Some(path) => {
+                let contents = fs::read_to_string(path)
+                    .with_context(|| format!("Failed to read params file '{path}'"))?;
+                parse_params_file(&contents)
+                    .with_context(|| format!("Invalid params file '{path}'"))?
+            }
+            None => vec![BTreeMap::new()],
+        };
+        if param_sets.is_empty() {
+            return Err(anyhow::anyhow!("Params file contains no parameter sets"));
+        }
+
+        // Bind every parameter set up front so the timed loop only measures queries
+        let bound = param_sets
+            .iter()
+            .map(|set| params::bind_parameters(&options.query, set, &self.params))
+            .collect::<Result<Vec<_>>>()?;
+
+        let workers = usize::from(options.concurrency.get());
+        let clients = Self::connect_workers(&self.connection_string, workers)?;
+        let issued = AtomicU64::new(0);
+        let started = Instant::now();
+
+        let results: Vec<(Vec<Sample>, ErrorLog)> = thread::scope(|scope| {
+            let workers: Vec<_> = (0..workers)
+                .map(|i| {
+                    let client = &clients[i / POOL_LIMIT];
+                    let (bound, issued) = (&bound, &issued);
+                    scope.spawn(move || run_worker(client, options, bound, issued, started))
+                })
+                .collect();
+            workers
+                .into_iter()
+                .map(|worker| {
+                    worker.join().unwrap_or_else(|payload| {
+                        let mut errors = ErrorLog::default();
+                        errors.record(format!("Worker panicked: {}", panic_message(&*payload)));
+                        (Vec::new(), errors)
+                    })
+                })
+                .collect()
+        });
+        let elapsed = started.elapsed();
+
+        let mut errors = ErrorLog::default();
+        let mut samples = Vec::new();
+        for (worker_samples, worker_errors) in results {
+            samples.extend(worker_samples);
+            errors.merge(worker_errors);
+        }
+        let mut client_ms: Vec<f64> = samples.iter().map(|s| s.client_ms).collect();
+        let mut server_ms: Vec<f64> = samples.iter().filter_map(|s| s.server_ms).collect();
+
+        let report = BenchReport {
+            options,
+            completed: samples.len() as u64,
+            errors,
+            elapsed,
+            client: LatencySummary::from_samples(&mut client_ms),
+            server: LatencySummary::from_samples(&mut server_ms),
+        };
+
+        if self.format == "json" {
+            println!("{}", serde_json::to_string_pretty(&report.to_json())?);
+        } else {
+            report.print_table();
+        }
+        Ok(())
+    }
+}
+
+/// Text of a panic payload, as passed to `panic!`.
+pub fn panic_message(payload: &(dyn Any + Send)) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|message| (*message).to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown cause".to_string())
+}
+
+/// Issues requests until the request budget or the duration is exhausted,
+/// returning the collected samples and the failed requests.
+fn run_worker(
+    client: &FalkorSyncClient,
+    options: &BenchOptions,
+    bound: &[HashMap<String, String>],
+    issued: &AtomicU64,
+    started: Instant,
+) -> (Vec<Sample>, ErrorLog) {
+    let mut graph = client.select_graph(&options.graph);
+    let mut samples = Vec::new();
+    let mut errors = ErrorLog::default();
+
+    loop {
+        if options.duration.is_some_and(|d| started.elapsed() >= d) {
+            break;
+        }
+        let i = issued.fetch_add(1, Ordering::Relaxed);
+        if options.duration.is_none() && i >= options.requests {
+            break;
+        }
+
+        #[allow(clippy::cast_possible_truncation)]
+        let params = &bound[(i % bound.len() as u64) as usize];
+        let request_started = Instant::now();
+        let result = if options.readonly {
+            graph.ro_query(&options.query).with_params(params).execute()
+        } else {
+            graph.query(&options.query).with_params(params).execute()
+        };
+
+        match result {
+            Ok(result) => {
+                let server_ms = result.get_internal_execution_time();
+                // Parse every row so client time includes decoding the result
+                result.data.for_each(drop);
+                samples.push(Sample {
+                    client_ms: request_started.elapsed().as_secs_f64() * 1000.0,
+                    server_ms,
+                });
+            }
+            Err(e) => errors.record(e.to_string()),
+        }
+    }
+
+    (samples, errors)
+}
+
+struct BenchReport<'a> {
+    options: &'a BenchOptions,
+    completed: u64,
+    errors: ErrorLog,
+    elapsed: Duration,
+    client: Option<LatencySummary>,
+    server: Option<LatencySummary>,
+}
+
+impl BenchReport<'_> {
+    #[allow(clippy::cast_precision_loss)]
+    fn throughput(&self) -> f64 {
+        let secs = self.elapsed.as_secs_f64();
+        if secs > 0.0 {
+            self.completed as f64 / secs
+        } else {
+            0.0
+        }
+    }
+
+    fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "graph": self.options.graph,
+            "query": self.options.query,
+            "concurrency": self.options.concurrency.get(),
+            "requests": self.completed,
+            "errors": self.errors.count,
+            "error_messages": self.errors.messages,
+            "duration_secs": self.elapsed.as_secs_f64(),
+            "throughput": self.throughput(),
+            "latency_ms": {
+                "client": self.client.map(LatencySummary::to_json),
+                "server": self.server.map(LatencySummary::to_json),
+            },
+        })
+    }
+
+    fn print_table(&self) {
+        println!("{}", "Benchmark:".cyan().bold());
+        println!("  Query: {}", self.options.query);
+        println!("  Graph: {}", self.options.graph);
+        println!("  Concurrency: {}", self.options.concurrency);
+        println!(
+            "  Requests: {} ({} errors)",
+            self.completed, self.errors.count
+        );
+        for message in &self.errors.messages {
+            println!("  {} {message}", "Error:".red());
+        }
+        println!("  Duration: {:.3} seconds", self.elapsed.as_secs_f64());
+        println!("  Throughput: {:.1} requests/second", self.throughput());
+        println!();
+
+        println!(
+            "{}",
+            format!(
+                "{:<8} {:>12} {:>12}",
+                "Latency", "Client (ms)", "Server (ms)"
+            )
+            .cyan()
+            .bold()
+        );
+        let cell = |summary: Option<LatencySummary>, i: usize| {
+            summary.map_or_else(|| "-".to_string(), |s| format!("{:.3}", s.values()[i]))
+        };
+        for (i, name) in STAT_NAMES.iter().enumerate() {
+            println!(
+                "{:<8} {:>12} {:>12}",
+                name,
+                cell(self.client, i),
+                cell(self.server, i)
+            );
+        }
+    }
+}
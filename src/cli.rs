@@ -1,4 +1,13 @@
-use clap::{Parser, Subcommand};
+use clap::builder::PossibleValuesParser;
+use clap::error::ErrorKind;
+use clap::parser::ValueSource;
+use clap::{CommandFactory, FromArgMatches, Parser, Subcommand};
+use std::ffi::OsString;
+use std::num::NonZeroU16;
+
+use crate::format::StatsMode;
+use crate::table::{Border, Overflow};
+use crate::theme::ColorChoice;
 
 /// `FalkorDB` Command Line Interface
 #[derive(Parser)]
@@ -41,22 +50,111 @@ pub struct Cli {
     #[arg(short = 'f', long)]
     pub file: Option<String>,
 
-    /// Output format (json, table, csv)
-    #[arg(long, default_value = "table")]
+    /// Output format
+    #[arg(
+        long,
+        global = true,
+        default_value = "table",
+        value_parser = PossibleValuesParser::new(crate::format::names())
+    )]
     pub format: String,
 
-    /// Quiet mode - suppress non-essential output
+    /// Write query results to this file, replaced atomically once the run
+    /// succeeds; .gz and .zst files are compressed
+    #[arg(short = 'o', long, global = true)]
+    pub output: Option<String>,
+
+    /// Node property shown as the node label in dot output (default: name, then title)
+    #[arg(long)]
+    pub display_property: Option<String>,
+
+    /// Embed a stylesheet before the table in html output
+    #[arg(long)]
+    pub html_style: bool,
+
+    /// End ndjson output with a line holding the query statistics
+    #[arg(long)]
+    pub ndjson_stats: bool,
+
+    /// Statistics shown with table output
+    #[arg(long, global = true, value_enum, default_value_t = StatsMode::Full)]
+    pub stats: StatsMode,
+
+    /// Characters used for table borders
+    #[arg(long, value_enum, default_value_t = Border::Ascii)]
+    pub border: Border,
+
+    /// How table cells too wide for the terminal are shown
+    #[arg(long, value_enum, default_value_t = Overflow::Truncate)]
+    pub overflow: Overflow,
+
+    /// Cut node and relationship property values in table cells to this many
+    /// characters (0 shows them in full)
+    #[arg(long, default_value = "40")]
+    pub property_limit: usize,
+
+    /// Quiet mode - suppress non-essential output; results still go to -o
     #[arg(short = 'q', long)]
     pub quiet: bool,
 
-    /// Raw output mode
+    /// Raw output: plain tab-separated values, one row per line, with no
+    /// header, statistics or colors
     #[arg(short = 'r', long)]
     pub raw: bool,
 
+    /// Run --eval or query commands this many times (-1 runs until interrupted)
+    #[arg(long, default_value = "1", allow_negative_numbers = true)]
+    pub repeat: i64,
+
+    /// Seconds to wait between repeated runs (fractions allowed)
+    #[arg(short = 'i', long, default_value = "0")]
+    pub interval: f64,
+
+    /// With --repeat, only print results that changed since the previous run
+    #[arg(long)]
+    pub changes_only: bool,
+
+    /// Never pipe output through $PAGER
+    #[arg(long)]
+    pub no_pager: bool,
+
+    /// When to color output; colors are themed in ~/.falkordb-cli.yaml
+    #[arg(long, value_enum, default_value_t = ColorChoice::Auto)]
+    pub color: ColorChoice,
+
     #[command(subcommand)]
     pub command: Option<Commands>,
 }
 
+impl Cli {
+    /// Parses arguments like [`Parser::try_parse_from`], and also rejects
+    /// output options that the chosen subcommand would silently ignore.
+    pub fn try_parse_checked<I, T>(args: I) -> Result<Self, clap::Error>
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<OsString> + Clone,
+    {
+        let matches = Self::command().try_get_matches_from(args)?;
+        let cli = Self::from_arg_matches(&matches)?;
+        if let (Some(command), Some(name)) = (&cli.command, matches.subcommand_name()) {
+            for (id, flag) in [
+                ("output", "--output"),
+                ("format", "--format"),
+                ("stats", "--stats"),
+            ] {
+                let given = matches.value_source(id) == Some(ValueSource::CommandLine);
+                if given && !command.accepts(id) {
+                    return Err(Self::command().error(
+                        ErrorKind::ArgumentConflict,
+                        format!("{flag} does not apply to the '{name}' subcommand"),
+                    ));
+                }
+            }
+        }
+        Ok(cli)
+    }
+}
+
 #[derive(Subcommand)]
 pub enum Commands {
     /// Execute a Cypher query on a graph
@@ -152,6 +250,206 @@ pub enum Commands {
         #[arg(short = 'a', long)]
         args: Option<String>,
     },
+    /// Benchmark a query and report throughput and latency percentiles
+    Bench {
+        /// Graph name
+        graph: String,
+        /// Cypher query
+        query: String,
+        /// Total number of requests (ignored when --duration is set)
+        #[arg(long, default_value = "1000")]
+        requests: u64,
+        /// Number of concurrent connections
+        #[arg(short = 'c', long, default_value = "1")]
+        concurrency: NonZeroU16,
+        /// Run for this many seconds instead of a fixed number of requests
+        #[arg(short = 'd', long)]
+        duration: Option<f64>,
+        /// File with one JSON object of query parameters per line, used in turn
+        #[arg(long)]
+        params_file: Option<String>,
+        /// Run the query as read-only
+        #[arg(long)]
+        readonly: bool,
+    },
+    /// Run a weighted mix of queries described by a YAML workload file
+    Load {
+        /// Graph name
+        graph: String,
+        /// Workload file (YAML)
+        workload: String,
+        /// Run time in seconds (overrides the workload file)
+        #[arg(short = 'd', long)]
+        duration: Option<f64>,
+        /// Target queries per second (overrides the workload file)
+        #[arg(long)]
+        rate: Option<f64>,
+        /// Number of concurrent connections (overrides the workload file)
+        #[arg(short = 'c', long)]
+        concurrency: Option<NonZeroU16>,
+    },
+    /// Write a Cypher script that recreates a graph to stdout
+    Dump {
+        /// Graph name
+        graph: String,
+        /// Number of nodes or relationships per statement
+        #[arg(long, default_value = "500", value_parser = clap::value_parser!(u64).range(1..))]
+        batch_size: u64,
+        /// Number of internal IDs fetched per query
+        #[arg(long, default_value = "10000", value_parser = clap::value_parser!(u64).range(1..))]
+        page_size: u64,
+    },
+    /// Copy a graph, on the same server or to another one
+    Copy {
+        /// Source graph name
+        source: String,
+        /// Destination graph name
+        destination: String,
+        /// Destination server URL (e.g. redis://host:6379); defaults to the current server
+        #[arg(long)]
+        to_url: Option<String>,
+        /// Replace the destination graph if it exists
+        #[arg(long)]
+        overwrite: bool,
+        /// Compare node and relationship counts after copying
+        #[arg(long)]
+        verify: bool,
+        /// Do not copy indices and constraints
+        #[arg(long)]
+        skip_indices: bool,
+        /// Number of nodes or relationships per write when streaming
+        #[arg(long, default_value = "500", value_parser = clap::value_parser!(u64).range(1..))]
+        batch_size: u64,
+        /// Number of internal IDs fetched per query when streaming
+        #[arg(long, default_value = "10000", value_parser = clap::value_parser!(u64).range(1..))]
+        page_size: u64,
+    },
+    /// Import data into a graph
+    Import {
+        #[command(subcommand)]
+        format: ImportFormat,
+    },
+    /// Export a graph to files
+    Export {
+        #[command(subcommand)]
+        format: ExportFormat,
+    },
     /// Interactive mode
     Interactive,
 }
+
+impl Commands {
+    /// Whether the subcommand uses the global `output`, `format` or `stats`
+    /// option. Only query results go to `--output`; bench and load honor
+    /// `--format json` for their reports.
+    fn accepts(&self, option: &str) -> bool {
+        match self {
+            Self::Query { .. } | Self::RoQuery { .. } | Self::Interactive => true,
+            Self::Bench { .. } | Self::Load { .. } => option == "format",
+            _ => false,
+        }
+    }
+}
+
+#[derive(Subcommand)]
+pub enum ImportFormat {
+    /// Import nodes and relationships from CSV files
+    Csv {
+        /// Graph name
+        graph: String,
+        /// Node file as LABEL=FILE; use LABEL1:LABEL2=FILE for several labels
+        #[arg(long = "nodes", value_name = "LABEL=FILE")]
+        nodes: Vec<String>,
+        /// Relationship file as TYPE=FILE
+        #[arg(long = "relationships", value_name = "TYPE=FILE")]
+        relationships: Vec<String>,
+        /// Number of rows sent per query
+        #[arg(long, default_value = "1000", value_parser = clap::value_parser!(u64).range(1..))]
+        batch_size: u64,
+        /// Field delimiter
+        #[arg(long, default_value = ",")]
+        delimiter: char,
+    },
+    /// Import nodes and edges from a JSON Lines file
+    Jsonl {
+        /// Graph name
+        graph: String,
+        /// File with one node or edge record per line
+        file: String,
+        /// Number of records sent per query
+        #[arg(long, default_value = "1000", value_parser = clap::value_parser!(u64).range(1..))]
+        batch_size: u64,
+    },
+    /// Import nodes and edges from a GraphML file
+    Graphml {
+        /// GraphML file
+        file: String,
+        /// Graph name
+        graph: String,
+        /// Number of nodes or edges sent per query
+        #[arg(long, default_value = "1000", value_parser = clap::value_parser!(u64).range(1..))]
+        batch_size: u64,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ExportFormat {
+    /// Export one CSV file per label and relationship type, plus a manifest
+    Csv {
+        /// Graph name
+        graph: String,
+        /// Output directory
+        #[arg(long)]
+        out: String,
+        /// Number of internal IDs fetched per query
+        #[arg(long, default_value = "10000", value_parser = clap::value_parser!(u64).range(1..))]
+        page_size: u64,
+    },
+    /// Export one Parquet file per label and relationship type, plus a manifest
+    Parquet {
+        /// Graph name
+        graph: String,
+        /// Output directory
+        #[arg(long)]
+        out: String,
+        /// Number of internal IDs fetched per query
+        #[arg(long, default_value = "10000", value_parser = clap::value_parser!(u64).range(1..))]
+        page_size: u64,
+    },
+    /// Export one Arrow IPC file per label and relationship type, plus a manifest
+    Arrow {
+        /// Graph name
+        graph: String,
+        /// Output directory
+        #[arg(long)]
+        out: String,
+        /// Number of internal IDs fetched per query
+        #[arg(long, default_value = "10000", value_parser = clap::value_parser!(u64).range(1..))]
+        page_size: u64,
+    },
+    /// Export nodes and edges as JSON Lines, one record per line
+    Jsonl {
+        /// Graph name
+        graph: String,
+        /// Output file (default: stdout)
+        #[arg(long)]
+        out: Option<String>,
+        /// Number of internal IDs fetched per query
+        #[arg(long, default_value = "10000", value_parser = clap::value_parser!(u64).range(1..))]
+        page_size: u64,
+    },
+    /// Export the graph, or a subgraph, as GraphML
+    Graphml {
+        /// Graph name
+        graph: String,
+        /// Output file (default: stdout)
+        #[arg(long)]
+        out: Option<String>,
+        /// Only export the nodes, edges and paths returned by this Cypher query
+        #[arg(long)]
+        query: Option<String>,
+        /// Number of internal IDs fetched per query
+        #[arg(long, default_value = "10000", value_parser = clap::value_parser!(u64).range(1..))]
+        page_size: u64,
+    },
+}
@@ -1,6 +1,26 @@
 use anyhow::{Context, Result};
 use colored::Colorize;
-use falkordb::{FalkorClientBuilder, FalkorConnectionInfo, FalkorSyncClient};
+use falkordb::{
+    Edge, FalkorClientBuilder, FalkorConnectionInfo, FalkorSyncClient, FalkorValue, LazyResultSet,
+    Node, QueryResult,
+};
+use std::collections::{BTreeMap, HashMap};
+use std::fs::File;
+use std::io::{self, BufWriter, IsTerminal, Write};
+use std::num::NonZeroU8;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use crate::format::{
+    self, FormatOptions, FormatterInfo, OutputFormatter, RawFormatter, ResultSummary, StatsMode,
+};
+use crate::output::{self, OutputFile};
+use crate::repeat::RepeatOptions;
+use crate::{pager, params};
+
+/// Connections in one client's pool, the most `NonZeroU8` allows.
+pub const POOL_LIMIT: usize = u8::MAX as usize;
 
 pub struct ConnectionConfig<'a> {
     pub hostname: &'a str,
@@ -12,10 +32,23 @@ pub struct ConnectionConfig<'a> {
 
 pub struct FalkorCli {
     pub client: FalkorSyncClient,
+    pub connection_string: String,
     pub current_graph: Option<String>,
     pub format: String,
     pub quiet: bool,
     pub raw: bool,
+    pub pager: bool,
+    pub output_file: Option<OutputFile>,
+    pub query_log: Option<File>,
+    pub last_statement: Option<String>,
+    pub echo: bool,
+    pub include_stack: Vec<PathBuf>,
+    pub params: BTreeMap<String, serde_json::Value>,
+    pub repeat: RepeatOptions,
+    pub format_options: FormatOptions,
+    /// Set once a binary result has been written to the current output,
+    /// which can only hold one
+    pub binary_written: AtomicBool,
 }
 
 impl FalkorCli {
@@ -44,23 +77,63 @@ impl FalkorCli {
             ),
         };
 
-        let connection_info = FalkorConnectionInfo::try_from(connection_string)
-            .context("Failed to create connection info")?;
-
-        let client = FalkorClientBuilder::new()
-            .with_connection_info(connection_info)
-            .build()
-            .context("Failed to create FalkorDB client")?;
+        let client = Self::connect(&connection_string, None)?;
 
         Ok(Self {
             client,
+            connection_string,
             current_graph: None,
             format,
             quiet,
             raw,
+            pager: true,
+            output_file: None,
+            query_log: None,
+            last_statement: None,
+            echo: false,
+            include_stack: Vec::new(),
+            params: BTreeMap::new(),
+            repeat: RepeatOptions::default(),
+            format_options: FormatOptions::default(),
+            binary_written: AtomicBool::new(false),
         })
     }
 
+    /// Opens a client for a connection string, optionally with a connection
+    /// pool of a specific size for concurrent work.
+    pub fn connect(
+        connection_string: &str,
+        connections: Option<NonZeroU8>,
+    ) -> Result<FalkorSyncClient> {
+        let connection_info = FalkorConnectionInfo::try_from(connection_string)
+            .context("Failed to create connection info")?;
+
+        let mut builder = FalkorClientBuilder::new().with_connection_info(connection_info);
+        if let Some(connections) = connections {
+            builder = builder.with_num_connections(connections);
+        }
+        builder.build().context("Failed to create FalkorDB client")
+    }
+
+    /// Opens enough clients to give each of `workers` threads a connection of
+    /// its own, since a pool holds at most [`POOL_LIMIT`] connections. Worker
+    /// `i` uses client `i / POOL_LIMIT`.
+    pub fn connect_workers(
+        connection_string: &str,
+        workers: usize,
+    ) -> Result<Vec<FalkorSyncClient>> {
+        (0..workers)
+            .step_by(POOL_LIMIT)
+            .map(|first| {
+                let size = u8::try_from(workers - first)
+                    .ok()
+                    .and_then(NonZeroU8::new)
+                    .unwrap_or(NonZeroU8::MAX);
+                Self::connect(connection_string, Some(size))
+            })
+            .collect()
+    }
+
     pub fn set_graph(&mut self, graph_name: String) {
         self.current_graph = Some(graph_name);
     }
@@ -77,136 +150,204 @@ impl FalkorCli {
     }
 
     pub fn execute_query(&self, graph_name: &str, query: &str, readonly: bool) -> Result<()> {
+        self.execute_query_with_params(graph_name, query, &BTreeMap::new(), readonly)
+    }
+
+    /// Executes a query, binding every `$parameter` it references from
+    /// `params` or, failing that, from the session parameters.
+    pub fn execute_query_with_params(
+        &self,
+        graph_name: &str,
+        query: &str,
+        params: &BTreeMap<String, serde_json::Value>,
+        readonly: bool,
+    ) -> Result<()> {
+        self.run_query(graph_name, query, params, readonly, |result, elapsed| {
+            self.emit_query_result(query, result, Some(elapsed))
+        })
+    }
+
+    /// Runs a query and hands the result, along with the time the server took
+    /// to reply, to `handle`. Failures are recorded in the query log before
+    /// being returned.
+    pub fn run_query<R>(
+        &self,
+        graph_name: &str,
+        query: &str,
+        params: &BTreeMap<String, serde_json::Value>,
+        readonly: bool,
+        handle: impl FnOnce(QueryResult<LazyResultSet<'_>>, Duration) -> Result<R>,
+    ) -> Result<R> {
+        let bound = params::bind_parameters(query, params, &self.params)?;
         let mut graph = self.client.select_graph(graph_name);
 
+        let started = Instant::now();
         let result = if readonly {
-            graph.ro_query(query).execute()
+            graph.ro_query(query).with_params(&bound).execute()
         } else {
-            graph.query(query).execute()
+            graph.query(query).with_params(&bound).execute()
         };
 
         match result {
-            Ok(query_result) => {
-                if !self.quiet {
-                    self.display_query_result(&query_result)?;
+            Ok(query_result) => handle(query_result, started.elapsed()),
+            Err(e) => {
+                if let Some(log) = &self.query_log {
+                    output::log_statement(log, query, format!("ERROR: {e}\n").as_bytes())?;
                 }
-                Ok(())
+                Err(anyhow::anyhow!("Query failed: {}", e))
             }
-            Err(e) => Err(anyhow::anyhow!("Query failed: {}", e)),
         }
     }
 
-    fn display_query_result(
+    /// Renders a result and sends it to the output file or the pager, logging
+    /// it along with the statement that produced it.
+    pub fn emit_query_result<I>(
         &self,
-        result: &falkordb::QueryResult<falkordb::LazyResultSet>,
-    ) -> Result<()> {
-        if self.raw {
-            println!(
-                "Raw result: headers={:?}, stats={:?}",
-                result.header, result.stats
-            );
-            return Ok(());
-        }
+        query: &str,
+        result: QueryResult<I>,
+        elapsed: Option<Duration>,
+    ) -> Result<()>
+    where
+        I: Iterator<Item = Vec<FalkorValue>>,
+    {
+        let info = format::lookup(&self.format)
+            .ok_or_else(|| anyhow::anyhow!("Unknown output format '{}'", self.format))?;
 
-        match self.format.as_str() {
-            "json" => Self::display_as_json(result),
-            "csv" => Self::display_as_csv(result),
-            _ => self.display_as_table(result),
+        if self.format_options.statistics_to_stderr && !self.quiet && !self.raw {
+            let summary = ResultSummary {
+                header: Vec::new(),
+                stats: result.stats.clone(),
+                elapsed,
+            };
+            format::write_statistics(
+                &summary,
+                self.format_options.stats,
+                &mut io::stderr().lock(),
+            )?;
         }
-    }
 
-    #[allow(clippy::unnecessary_wraps)]
-    fn display_as_table(
-        &self,
-        result: &falkordb::QueryResult<falkordb::LazyResultSet>,
-    ) -> Result<()> {
-        // Display statistics
-        if !self.quiet {
-            println!("{}", "Statistics:".cyan().bold());
-            println!(
-                "  Nodes created: {}",
-                result.get_nodes_created().unwrap_or(0)
-            );
-            println!(
-                "  Nodes deleted: {}",
-                result.get_nodes_deleted().unwrap_or(0)
-            );
-            println!(
-                "  Relationships created: {}",
-                result.get_relationship_created().unwrap_or(0)
-            );
-            println!(
-                "  Relationships deleted: {}",
-                result.get_relationship_deleted().unwrap_or(0)
-            );
-            println!(
-                "  Properties set: {}",
-                result.get_properties_set().unwrap_or(0)
-            );
-            if let Some(time) = result.get_internal_execution_time() {
-                println!("  Query internal execution time: {time:.3} milliseconds");
+        // Quiet mode keeps results off the screen, but the output file and
+        // the query log still get them
+        if self.quiet && self.output_file.is_none() && (info.binary || self.query_log.is_none()) {
+            if let Some(log) = &self.query_log {
+                output::log_statement(log, query, b"")?;
             }
-            println!();
+            return Ok(());
         }
 
-        // Display results if any
-        let headers = &result.header;
-        if !headers.is_empty() {
-            // Print headers
-            print!("| ");
-            for header in headers {
-                print!("{:15} | ", header.cyan().bold());
+        // Streaming formats are written as rows are read. The query log needs
+        // the whole output, so logging falls back to buffering, except for
+        // binary formats, which are only noted in the log
+        if !self.raw && (info.binary || (info.streaming && self.query_log.is_none())) {
+            let rows = self.stream_query_result(info, result, elapsed)?;
+            if let Some(log) = &self.query_log {
+                let note = format!("({rows} rows written as {})\n", info.name);
+                output::log_statement(log, query, note.as_bytes())?;
             }
-            println!();
-
-            // Print separator
-            print!("|");
-            for _ in headers {
-                print!("-----------------+");
-            }
-            println!();
+            return Ok(());
+        }
 
-            // Note: We can't iterate over the LazyResultSet because it requires mutable access
-            // This is a limitation of the current implementation
-            println!(
-                "| {:15} |",
-                "Data available but iteration requires mutable access"
-            );
+        // Without a pager, log or output file nothing needs the whole
+        // output, so it goes straight to stdout
+        let paging = self.pager && io::stdout().is_terminal();
+        if !paging && self.query_log.is_none() && self.output_file.is_none() {
+            let mut out = BufWriter::new(io::stdout().lock());
+            let written = self
+                .display_query_result(result, elapsed, &mut out)
+                .and_then(|()| Ok(out.flush()?));
+            return ignore_broken_pipe(written);
         }
 
+        // Render into a buffer first so the pager can decide whether
+        // the output fits on one screen
+        let mut buffer = Vec::new();
+        self.display_query_result(result, elapsed, &mut buffer)?;
+        if let Some(log) = &self.query_log {
+            output::log_statement(log, query, &buffer)?;
+        }
+        match &self.output_file {
+            Some(file) => {
+                file.write_with(|out| Ok(out.write_all(&output::strip_ansi(&buffer))?))?
+            }
+            None if self.quiet => {}
+            None => pager::write_paged(&buffer, self.pager)?,
+        }
         Ok(())
     }
 
-    fn display_as_json(result: &falkordb::QueryResult<falkordb::LazyResultSet>) -> Result<()> {
-        println!(
-            "{}",
-            serde_json::to_string_pretty(&serde_json::json!({
-                "statistics": {
-                    "nodes_created": result.get_nodes_created().unwrap_or(0),
-                    "nodes_deleted": result.get_nodes_deleted().unwrap_or(0),
-                    "relationships_created": result.get_relationship_created().unwrap_or(0),
-                    "relationships_deleted": result.get_relationship_deleted().unwrap_or(0),
-                    "properties_set": result.get_properties_set().unwrap_or(0),
-                    "query_time": result.get_internal_execution_time().unwrap_or(0.0),
-                },
-                "headers": result.header,
-                "data": "Result data serialization would be implemented here"
-            }))?
-        );
-        Ok(())
+    fn display_query_result<I>(
+        &self,
+        result: QueryResult<I>,
+        elapsed: Option<Duration>,
+        out: &mut dyn Write,
+    ) -> Result<()>
+    where
+        I: Iterator<Item = Vec<FalkorValue>>,
+    {
+        // Tables shown on the terminal are fitted to its width
+        let mut options = self.result_options();
+        if self.output_file.is_none() {
+            options.max_width = pager::terminal_width();
+        }
+        let mut formatter: Box<dyn OutputFormatter> = if self.raw {
+            Box::new(RawFormatter)
+        } else {
+            format::lookup(&self.format)
+                .ok_or_else(|| anyhow::anyhow!("Unknown output format '{}'", self.format))?
+                .create(&options)
+        };
+        format::render(formatter.as_mut(), result, elapsed, out).map(drop)
     }
 
-    #[allow(clippy::unnecessary_wraps)]
-    fn display_as_csv(result: &falkordb::QueryResult<falkordb::LazyResultSet>) -> Result<()> {
-        let headers = &result.header;
-        if !headers.is_empty() {
-            // Print headers
-            println!("{}", headers.join(","));
+    /// Options for rendering results; quiet mode leaves statistics out.
+    fn result_options(&self) -> FormatOptions {
+        let mut options = self.format_options.clone();
+        if self.quiet {
+            options.stats = StatsMode::None;
+        }
+        options
+    }
 
-            // Note: We can't iterate over the LazyResultSet because it requires mutable access
-            println!("Data rows would be printed here if we had mutable access to the result set");
+    /// Writes a result to the output file or stdout row by row, without
+    /// buffering or paging it. Returns the number of rows written.
+    fn stream_query_result<I>(
+        &self,
+        info: &FormatterInfo,
+        result: QueryResult<I>,
+        elapsed: Option<Duration>,
+    ) -> Result<u64>
+    where
+        I: Iterator<Item = Vec<FalkorValue>>,
+    {
+        // Concatenated Parquet or Arrow files cannot be read back
+        if info.binary && self.binary_written.load(Ordering::Relaxed) {
+            return Err(anyhow::anyhow!(
+                "{} output holds a single result; run one query per file or use \\o to switch files",
+                info.name
+            ));
         }
-        Ok(())
+        let mut formatter = info.create(&self.result_options());
+        let written = match &self.output_file {
+            Some(file) => {
+                file.write_with(|out| format::render(formatter.as_mut(), result, elapsed, out))
+            }
+            None if info.binary && io::stdout().is_terminal() => {
+                return Err(anyhow::anyhow!(
+                    "{} output is binary; use --output FILE or redirect stdout",
+                    info.name
+                ));
+            }
+            None => {
+                let mut out = BufWriter::new(io::stdout().lock());
+                format::render(formatter.as_mut(), result, elapsed, &mut out)
+                    .and_then(|rows| Ok(out.flush().map(|()| rows)?))
+            }
+        };
+        let rows = ignore_broken_pipe(written)?;
+        if info.binary {
+            self.binary_written.store(true, Ordering::Relaxed);
+        }
+        Ok(rows)
     }
 
     #[allow(clippy::unnecessary_wraps)]
@@ -253,3 +394,98 @@ impl FalkorCli {
         Ok(())
     }
 }
+
+/// Renders a single value as plain text for table and CSV cells.
+///
+/// Scalars are printed as-is; nodes, edges, paths and collections fall back to
+/// their compact JSON representation.
+pub fn value_to_string(value: &FalkorValue) -> String {
+    match value {
+        FalkorValue::String(s) => s.clone(),
+        FalkorValue::Bool(b) => b.to_string(),
+        FalkorValue::I64(i) => i.to_string(),
+        FalkorValue::F64(f) => f.to_string(),
+        FalkorValue::None => "null".to_string(),
+        FalkorValue::Unparseable(s) => format!("<unparseable: {s}>"),
+        _ => value_to_json(value).to_string(),
+    }
+}
+
+/// Converts a value into its JSON representation.
+///
+/// Nodes become `{"id", "labels", "properties"}` objects and edges become
+/// `{"id", "relationship", "src", "dst", "properties"}` objects.
+pub fn value_to_json(value: &FalkorValue) -> serde_json::Value {
+    match value {
+        FalkorValue::Node(node) => node_to_json(node),
+        FalkorValue::Edge(edge) => edge_to_json(edge),
+        FalkorValue::Path(path) => serde_json::json!({
+            "nodes": path.nodes.iter().map(node_to_json).collect::<Vec<_>>(),
+            "relationships": path.relationships.iter().map(edge_to_json).collect::<Vec<_>>(),
+        }),
+        FalkorValue::Array(items) => {
+            serde_json::Value::Array(items.iter().map(value_to_json).collect())
+        }
+        FalkorValue::Map(map) => properties_to_json(map),
+        FalkorValue::Vec32(vec) => serde_json::json!(vec.values),
+        FalkorValue::String(s) | FalkorValue::Unparseable(s) => serde_json::json!(s),
+        FalkorValue::Bool(b) => serde_json::json!(b),
+        FalkorValue::I64(i) => serde_json::json!(i),
+        FalkorValue::F64(f) => serde_json::json!(f),
+        FalkorValue::Point(point) => serde_json::json!({
+            "latitude": point.latitude,
+            "longitude": point.longitude,
+        }),
+        FalkorValue::None => serde_json::Value::Null,
+    }
+}
+
+pub fn node_to_json(node: &Node) -> serde_json::Value {
+    serde_json::json!({
+        "id": node.entity_id,
+        "labels": node.labels,
+        "properties": properties_to_json(&node.properties),
+    })
+}
+
+pub fn edge_to_json(edge: &Edge) -> serde_json::Value {
+    serde_json::json!({
+        "id": edge.entity_id,
+        "relationship": edge.relationship_type,
+        "src": edge.src_node_id,
+        "dst": edge.dst_node_id,
+        "properties": properties_to_json(&edge.properties),
+    })
+}
+
+fn properties_to_json(properties: &HashMap<String, FalkorValue>) -> serde_json::Value {
+    // Sort keys so output is stable between runs
+    let sorted: BTreeMap<&String, serde_json::Value> = properties
+        .iter()
+        .map(|(key, value)| (key, value_to_json(value)))
+        .collect();
+    serde_json::json!(sorted)
+}
+
+/// Quotes a CSV field when it contains a separator, quote or newline.
+pub fn csv_escape(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Treats a closed stdout as success: the reader, e.g. `head`, may stop
+/// before the last row.
+fn ignore_broken_pipe<T: Default>(written: Result<T>) -> Result<T> {
+    match written {
+        Err(e)
+            if e.downcast_ref::<io::Error>()
+                .is_some_and(|e| e.kind() == io::ErrorKind::BrokenPipe) =>
+        {
+            Ok(T::default())
+        }
+        written => written,
+    }
+}
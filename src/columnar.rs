@@ -0,0 +1,388 @@
+use anyhow::Result;
+use arrow::array::{
+    ArrayRef, BooleanArray, BooleanBuilder, Float64Array, Int64Array, ListArray, ListBuilder,
+    StringArray, StringBuilder,
+};
+use arrow::datatypes::{DataType, Field, Float64Type, Int64Type, Schema, SchemaRef};
+use arrow::ipc::writer::FileWriter;
+use arrow::record_batch::RecordBatch;
+use falkordb::FalkorValue;
+use parquet::arrow::ArrowWriter;
+use parquet::basic::Compression;
+use parquet::file::properties::WriterProperties;
+use std::io::{self, Write};
+use std::sync::{Arc, Mutex};
+
+use crate::client::value_to_json;
+use crate::export::{ColumnType, ObservedType};
+use crate::format::{OutputFormatter, ResultSummary};
+
+/// Rows buffered per row group (Parquet) or record batch (Arrow IPC). Column
+/// types of a query result are inferred from the first one.
+pub const ROW_GROUP_SIZE: usize = 10_000;
+
+/// Binary columnar file formats.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ColumnarFormat {
+    Parquet,
+    /// Arrow IPC file format, also known as Feather v2
+    Arrow,
+}
+
+impl ColumnarFormat {
+    pub const fn extension(self) -> &'static str {
+        match self {
+            Self::Parquet => "parquet",
+            Self::Arrow => "arrow",
+        }
+    }
+}
+
+/// Arrow type of a column. Nodes, edges, paths, maps and points are written
+/// as JSON text; arrays of scalars become lists.
+pub fn arrow_type(observed: ObservedType) -> DataType {
+    let scalar = match observed.kind() {
+        Some(ColumnType::Int) => DataType::Int64,
+        Some(ColumnType::Float) => DataType::Float64,
+        Some(ColumnType::Boolean) => DataType::Boolean,
+        Some(ColumnType::String) | None => DataType::Utf8,
+    };
+    if observed.is_array() {
+        DataType::new_list(scalar, true)
+    } else {
+        scalar
+    }
+}
+
+enum Sink<W: Write + Send> {
+    Parquet(ArrowWriter<W>),
+    Arrow(FileWriter<W>),
+}
+
+/// Writes rows of JSON values to a Parquet or Arrow IPC file one row group at
+/// a time. Column types are either given up front or inferred from the first
+/// row group. Later whole-number floats are stored in integer columns; any
+/// other value that does not fit its column is an error.
+pub struct ColumnarWriter<W: Write + Send> {
+    format: ColumnarFormat,
+    out: Option<W>,
+    header: Vec<String>,
+    columns: Option<Vec<ObservedType>>,
+    sink: Option<Sink<W>>,
+    schema: Option<SchemaRef>,
+    pending: Vec<Vec<serde_json::Value>>,
+    rows: u64,
+}
+
+impl<W: Write + Send> ColumnarWriter<W> {
+    pub const fn new(
+        format: ColumnarFormat,
+        out: W,
+        header: Vec<String>,
+        columns: Option<Vec<ObservedType>>,
+    ) -> Self {
+        Self {
+            format,
+            out: Some(out),
+            header,
+            columns,
+            sink: None,
+            schema: None,
+            pending: Vec::new(),
+            rows: 0,
+        }
+    }
+
+    pub fn push(&mut self, row: Vec<serde_json::Value>) -> Result<()> {
+        self.pending.push(row);
+        if self.pending.len() >= ROW_GROUP_SIZE {
+            self.write_pending()?;
+        }
+        Ok(())
+    }
+
+    /// Writes the remaining rows and the file footer. Returns the number of
+    /// rows written.
+    pub fn finish(mut self) -> Result<u64> {
+        if !self.pending.is_empty() || self.sink.is_none() {
+            self.write_pending()?;
+        }
+        match self.sink.take() {
+            Some(Sink::Parquet(writer)) => {
+                writer.close()?;
+            }
+            Some(Sink::Arrow(mut writer)) => writer.finish()?,
+            None => {}
+        }
+        Ok(self.rows)
+    }
+
+    fn write_pending(&mut self) -> Result<()> {
+        let columns = match &self.columns {
+            Some(columns) => columns.clone(),
+            None => {
+                let mut columns = vec![ObservedType::default(); self.header.len()];
+                for row in &self.pending {
+                    for (column, value) in columns.iter_mut().zip(row) {
+                        if !value.is_null() {
+                            column.observe(value);
+                        }
+                    }
+                }
+                self.columns = Some(columns.clone());
+                columns
+            }
+        };
+
+        let schema = match &self.schema {
+            Some(schema) => Arc::clone(schema),
+            None => {
+                let fields: Vec<Field> = self
+                    .header
+                    .iter()
+                    .zip(&columns)
+                    .map(|(name, observed)| Field::new(name, arrow_type(*observed), true))
+                    .collect();
+                let schema = Arc::new(Schema::new(fields));
+                self.schema = Some(Arc::clone(&schema));
+                schema
+            }
+        };
+        if self.sink.is_none() {
+            let out = self
+                .out
+                .take()
+                .ok_or_else(|| anyhow::anyhow!("Writer already finished"))?;
+            self.sink = Some(match self.format {
+                ColumnarFormat::Parquet => {
+                    let properties = WriterProperties::builder()
+                        .set_compression(Compression::SNAPPY)
+                        .set_max_row_group_row_count(Some(ROW_GROUP_SIZE))
+                        .build();
+                    Sink::Parquet(ArrowWriter::try_new(
+                        out,
+                        Arc::clone(&schema),
+                        Some(properties),
+                    )?)
+                }
+                ColumnarFormat::Arrow => Sink::Arrow(FileWriter::try_new(out, &schema)?),
+            });
+        }
+
+        let mut arrays = Vec::with_capacity(columns.len());
+        for (index, (name, observed)) in self.header.iter().zip(&columns).enumerate() {
+            let values: Vec<&serde_json::Value> = self
+                .pending
+                .iter()
+                .map(|row| row.get(index).unwrap_or(&serde_json::Value::Null))
+                .collect();
+            if let Some(value) = values.iter().find(|value| !fits(*observed, value)) {
+                return Err(anyhow::anyhow!(
+                    "Column '{name}' holds {} values, but a later row has {value}; \
+                     convert the column in the query, e.g. with toFloat() or toString()",
+                    arrow_type(*observed)
+                ));
+            }
+            arrays.push(build_array(*observed, &values));
+        }
+        let batch = RecordBatch::try_new(schema, arrays)?;
+
+        match &mut self.sink {
+            Some(Sink::Parquet(writer)) => {
+                writer.write(&batch)?;
+                // Close the row group so memory stays bounded
+                writer.flush()?;
+            }
+            Some(Sink::Arrow(writer)) => writer.write(&batch)?,
+            None => {}
+        }
+        self.rows += self.pending.len() as u64;
+        self.pending.clear();
+        Ok(())
+    }
+}
+
+/// In-memory sink shared by a [`ColumnarFormatter`] and its writer. The
+/// writer needs an owned `Send` output, so bytes land here and are moved to
+/// the formatter's output after every row.
+#[derive(Clone, Default)]
+struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+impl SharedBuffer {
+    fn drain_into(&self, out: &mut dyn Write) -> Result<()> {
+        let mut buffer = self
+            .0
+            .lock()
+            .map_err(|_| anyhow::anyhow!("Output buffer lock poisoned"))?;
+        if !buffer.is_empty() {
+            out.write_all(&buffer)?;
+            buffer.clear();
+        }
+        Ok(())
+    }
+}
+
+impl Write for SharedBuffer {
+    fn write(&mut self, bytes: &[u8]) -> io::Result<usize> {
+        self.0
+            .lock()
+            .map_err(|_| io::Error::other("Output buffer lock poisoned"))?
+            .extend_from_slice(bytes);
+        Ok(bytes.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// `--format parquet` and `--format arrow`, with column types inferred from
+/// the first row group.
+pub struct ColumnarFormatter {
+    format: ColumnarFormat,
+    buffer: SharedBuffer,
+    writer: Option<ColumnarWriter<SharedBuffer>>,
+}
+
+impl ColumnarFormatter {
+    pub fn new(format: ColumnarFormat) -> Self {
+        Self {
+            format,
+            buffer: SharedBuffer::default(),
+            writer: None,
+        }
+    }
+}
+
+impl OutputFormatter for ColumnarFormatter {
+    fn begin(&mut self, summary: &ResultSummary, _out: &mut dyn Write) -> Result<()> {
+        self.writer = Some(ColumnarWriter::new(
+            self.format,
+            self.buffer.clone(),
+            summary.header.clone(),
+            None,
+        ));
+        Ok(())
+    }
+
+    fn row(&mut self, row: &[FalkorValue], out: &mut dyn Write) -> Result<()> {
+        let writer = self
+            .writer
+            .as_mut()
+            .ok_or_else(|| anyhow::anyhow!("Row written before the header"))?;
+        writer.push(row.iter().map(value_to_json).collect())?;
+        self.buffer.drain_into(out)
+    }
+
+    fn end(&mut self, out: &mut dyn Write) -> Result<()> {
+        if let Some(writer) = self.writer.take() {
+            writer.finish()?;
+        }
+        self.buffer.drain_into(out)
+    }
+}
+
+/// Whether a value can be stored in a column of the given type.
+fn fits(column: ObservedType, value: &serde_json::Value) -> bool {
+    if value.is_null() {
+        return true;
+    }
+    let mut merged = column;
+    merged.observe(value);
+    // Columns that only held nulls or empty arrays so far are text
+    if merged == column || (column.kind().is_none() && merged.is_array() == column.is_array()) {
+        return true;
+    }
+    // Whole-number floats are stored in integer columns without loss
+    let whole = |value: &serde_json::Value| value.is_null() || as_int(value).is_some();
+    column.kind() == Some(ColumnType::Int)
+        && match value {
+            serde_json::Value::Array(items) => column.is_array() && items.iter().all(whole),
+            value => !column.is_array() && whole(value),
+        }
+}
+
+/// Reads an integer, accepting floats without a fractional part.
+fn as_int(value: &serde_json::Value) -> Option<i64> {
+    #[allow(clippy::cast_possible_truncation, clippy::cast_precision_loss)]
+    value.as_i64().or_else(|| {
+        value
+            .as_f64()
+            .filter(|f| f.fract() == 0.0 && f.abs() < i64::MAX as f64)
+            .map(|f| f as i64)
+    })
+}
+
+fn build_array(observed: ObservedType, values: &[&serde_json::Value]) -> ArrayRef {
+    if observed.is_array() {
+        let items = |value: &serde_json::Value| value.as_array().cloned();
+        return match observed.kind() {
+            Some(ColumnType::Int) => Arc::new(ListArray::from_iter_primitive::<Int64Type, _, _>(
+                values.iter().map(|value| {
+                    items(value).map(|items| items.iter().map(as_int).collect::<Vec<_>>())
+                }),
+            )),
+            Some(ColumnType::Float) => Arc::new(
+                ListArray::from_iter_primitive::<Float64Type, _, _>(values.iter().map(|value| {
+                    items(value).map(|items| {
+                        items
+                            .iter()
+                            .map(serde_json::Value::as_f64)
+                            .collect::<Vec<_>>()
+                    })
+                })),
+            ),
+            Some(ColumnType::Boolean) => {
+                let mut builder = ListBuilder::new(BooleanBuilder::new());
+                for value in values {
+                    match items(value) {
+                        Some(items) => {
+                            for item in &items {
+                                builder.values().append_option(item.as_bool());
+                            }
+                            builder.append(true);
+                        }
+                        None => builder.append(false),
+                    }
+                }
+                Arc::new(builder.finish())
+            }
+            Some(ColumnType::String) | None => {
+                let element = observed.element();
+                let mut builder = ListBuilder::new(StringBuilder::new());
+                for value in values {
+                    match items(value) {
+                        Some(items) => {
+                            for item in &items {
+                                if item.is_null() {
+                                    builder.values().append_null();
+                                } else {
+                                    builder.values().append_value(element.format(item));
+                                }
+                            }
+                            builder.append(true);
+                        }
+                        None => builder.append(false),
+                    }
+                }
+                Arc::new(builder.finish())
+            }
+        };
+    }
+
+    match observed.kind() {
+        Some(ColumnType::Int) => Arc::new(values.iter().map(|v| as_int(v)).collect::<Int64Array>()),
+        Some(ColumnType::Float) => {
+            Arc::new(values.iter().map(|v| v.as_f64()).collect::<Float64Array>())
+        }
+        Some(ColumnType::Boolean) => {
+            Arc::new(values.iter().map(|v| v.as_bool()).collect::<BooleanArray>())
+        }
+        Some(ColumnType::String) | None => Arc::new(
+            values
+                .iter()
+                .map(|v| (!v.is_null()).then(|| observed.format(v)))
+                .collect::<StringArray>(),
+        ),
+    }
+}
@@ -1,8 +1,18 @@
 use anyhow::Result;
 use colored::Colorize;
+use std::time::Duration;
 
-use crate::cli::Commands;
+use crate::bench::BenchOptions;
+use crate::cli::{Commands, ExportFormat, ImportFormat};
 use crate::client::FalkorCli;
+use crate::columnar::ColumnarFormat;
+use crate::copy::CopyOptions;
+use crate::dump::DumpOptions;
+use crate::export::{JsonlExportOptions, TableExportOptions, TableFormat};
+use crate::graphml::{GraphmlExportOptions, GraphmlImportOptions};
+use crate::import::{CsvImportOptions, JsonlImportOptions};
+use crate::load::LoadOptions;
+use crate::params;
 
 impl FalkorCli {
     #[allow(clippy::too_many_lines)]
@@ -11,13 +21,27 @@ impl FalkorCli {
             Commands::Query {
                 graph,
                 query,
-                params: _,
-            } => self.execute_query(&graph, &query, false),
+                params,
+            } => {
+                let params = params
+                    .as_deref()
+                    .map(params::parse_params_object)
+                    .transpose()?
+                    .unwrap_or_default();
+                self.execute_query_repeated(&graph, &query, &params, false)
+            }
             Commands::RoQuery {
                 graph,
                 query,
-                params: _,
-            } => self.execute_query(&graph, &query, true),
+                params,
+            } => {
+                let params = params
+                    .as_deref()
+                    .map(params::parse_params_object)
+                    .transpose()?
+                    .unwrap_or_default();
+                self.execute_query_repeated(&graph, &query, &params, true)
+            }
             Commands::Profile { graph, query } => {
                 let mut graph_client = self.client.select_graph(&graph);
                 match graph_client.profile(&query).execute() {
@@ -159,7 +183,178 @@ impl FalkorCli {
                 println!("Use: graph.call_procedure(\"{procedure}\").execute() in the Rust API");
                 Ok(())
             }
+            Commands::Bench {
+                graph,
+                query,
+                requests,
+                concurrency,
+                duration,
+                params_file,
+                readonly,
+            } => {
+                let duration = duration
+                    .map(|secs| {
+                        if secs.is_finite() && secs > 0.0 {
+                            Ok(Duration::from_secs_f64(secs))
+                        } else {
+                            Err(anyhow::anyhow!("--duration must be a positive number"))
+                        }
+                    })
+                    .transpose()?;
+                self.run_bench(&BenchOptions {
+                    graph,
+                    query,
+                    requests,
+                    concurrency,
+                    duration,
+                    params_file,
+                    readonly,
+                })
+            }
+            Commands::Load {
+                graph,
+                workload,
+                duration,
+                rate,
+                concurrency,
+            } => self.run_load(&LoadOptions {
+                graph,
+                workload,
+                duration,
+                rate,
+                concurrency,
+            }),
+            Commands::Dump {
+                graph,
+                batch_size,
+                page_size,
+            } => self.dump_graph(&DumpOptions {
+                graph,
+                batch_size: usize::try_from(batch_size).unwrap_or(usize::MAX),
+                page_size,
+            }),
+            Commands::Copy {
+                source,
+                destination,
+                to_url,
+                overwrite,
+                verify,
+                skip_indices,
+                batch_size,
+                page_size,
+            } => self.copy_graph(&CopyOptions {
+                source,
+                destination,
+                to_url,
+                overwrite,
+                verify,
+                skip_indices,
+                batch_size: usize::try_from(batch_size).unwrap_or(usize::MAX),
+                page_size,
+            }),
+            Commands::Import { format } => self.handle_import(format),
+            Commands::Export { format } => self.handle_export(format),
             Commands::Interactive => self.interactive_mode(),
         }
     }
+
+    fn handle_import(&self, format: ImportFormat) -> Result<()> {
+        match format {
+            ImportFormat::Csv {
+                graph,
+                nodes,
+                relationships,
+                batch_size,
+                delimiter,
+            } => {
+                if nodes.is_empty() && relationships.is_empty() {
+                    return Err(anyhow::anyhow!(
+                        "Nothing to import: pass --nodes and/or --relationships"
+                    ));
+                }
+                let delimiter = u8::try_from(delimiter)
+                    .map_err(|_| anyhow::anyhow!("--delimiter must be an ASCII character"))?;
+                self.import_csv(&CsvImportOptions {
+                    graph,
+                    nodes,
+                    relationships,
+                    batch_size: usize::try_from(batch_size).unwrap_or(usize::MAX),
+                    delimiter,
+                })
+            }
+            ImportFormat::Jsonl {
+                graph,
+                file,
+                batch_size,
+            } => self.import_jsonl(&JsonlImportOptions {
+                graph,
+                file,
+                batch_size: usize::try_from(batch_size).unwrap_or(usize::MAX),
+            }),
+            ImportFormat::Graphml {
+                file,
+                graph,
+                batch_size,
+            } => self.import_graphml(&GraphmlImportOptions {
+                file,
+                graph,
+                batch_size: usize::try_from(batch_size).unwrap_or(usize::MAX),
+            }),
+        }
+    }
+
+    fn handle_export(&self, format: ExportFormat) -> Result<()> {
+        match format {
+            ExportFormat::Csv {
+                graph,
+                out,
+                page_size,
+            } => self.export_tables(&TableExportOptions {
+                graph,
+                out: out.into(),
+                page_size,
+                format: TableFormat::Csv,
+            }),
+            ExportFormat::Parquet {
+                graph,
+                out,
+                page_size,
+            } => self.export_tables(&TableExportOptions {
+                graph,
+                out: out.into(),
+                page_size,
+                format: TableFormat::Columnar(ColumnarFormat::Parquet),
+            }),
+            ExportFormat::Arrow {
+                graph,
+                out,
+                page_size,
+            } => self.export_tables(&TableExportOptions {
+                graph,
+                out: out.into(),
+                page_size,
+                format: TableFormat::Columnar(ColumnarFormat::Arrow),
+            }),
+            ExportFormat::Jsonl {
+                graph,
+                out,
+                page_size,
+            } => self.export_jsonl(&JsonlExportOptions {
+                graph,
+                out: out.map(Into::into),
+                page_size,
+            }),
+            ExportFormat::Graphml {
+                graph,
+                out,
+                query,
+                page_size,
+            } => self.export_graphml(&GraphmlExportOptions {
+                graph,
+                out: out.map(Into::into),
+                query,
+                page_size,
+            }),
+        }
+    }
 }
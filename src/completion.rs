@@ -33,6 +33,17 @@ impl SimpleCompleter {
             "INTERACTIVE",
             "QUERY",
             "RO-QUERY",
+            "\\PAGER",
+            "\\O",
+            "\\LOG",
+            "\\FORMAT",
+            "\\E",
+            "\\I",
+            "\\ECHO",
+            "SOURCE",
+            "\\PARAM",
+            "\\PARAMS",
+            "\\CONSTRAINT",
         ];
         for e in extras {
             if !keywords.contains(&e.to_string()) {
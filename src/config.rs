@@ -0,0 +1,42 @@
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::theme::Theme;
+
+/// Settings read from the config file.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub theme: Theme,
+}
+
+/// Location of the config file: `$FALKORDB_CLI_CONFIG` when set, otherwise
+/// `~/.falkordb-cli.yaml`.
+pub fn config_path() -> PathBuf {
+    if let Ok(path) = env::var("FALKORDB_CLI_CONFIG") {
+        return PathBuf::from(path);
+    }
+    env::var("HOME")
+        .or_else(|_| env::var("USERPROFILE"))
+        .map_or_else(|_| PathBuf::new(), PathBuf::from)
+        .join(".falkordb-cli.yaml")
+}
+
+/// Reads the config file. A missing file gives the defaults.
+pub fn load_config() -> Result<Config> {
+    let path = config_path();
+    let Ok(text) = fs::read_to_string(&path) else {
+        return Ok(Config::default());
+    };
+    parse_config(&text).with_context(|| format!("Invalid config file '{}'", path.display()))
+}
+
+pub fn parse_config(text: &str) -> Result<Config> {
+    if text.trim().is_empty() {
+        return Ok(Config::default());
+    }
+    Ok(serde_yaml::from_str(text)?)
+}
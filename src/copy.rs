@@ -0,0 +1,191 @@
+use anyhow::Result;
+use colored::Colorize;
+use falkordb::{FalkorValue, SyncGraph};
+use std::net::{IpAddr, ToSocketAddrs};
+
+use crate::client::FalkorCli;
+use crate::dump::{
+    apply_constraint, cleanup_statements, import_index_statement, read_schema, stream_entities,
+};
+use crate::export::EntityKind;
+
+/// Settings for the `copy` subcommand.
+pub struct CopyOptions {
+    pub source: String,
+    pub destination: String,
+    /// Connection URL of the destination server; the current server when unset
+    pub to_url: Option<String>,
+    pub overwrite: bool,
+    pub verify: bool,
+    pub skip_indices: bool,
+    pub batch_size: usize,
+    pub page_size: u64,
+}
+
+/// Counts the nodes or relationships of a graph.
+pub fn count_entities(graph: &mut SyncGraph, kind: EntityKind) -> Result<i64> {
+    let count = graph
+        .ro_query(&format!("MATCH {} RETURN count(e)", kind.pattern()))
+        .execute()
+        .map_err(|e| anyhow::anyhow!("Query failed: {e}"))?
+        .data
+        .next()
+        .and_then(|row| row.into_iter().next());
+    match count {
+        Some(FalkorValue::I64(count)) => Ok(count),
+        _ => Err(anyhow::anyhow!("Unexpected count result")),
+    }
+}
+
+/// Whether two connection URLs reach the same database: the same port and
+/// database number on a host that resolves to a shared address, so
+/// `localhost` and `127.0.0.1` match. Unparseable URLs never match.
+pub fn same_server(a: &str, b: &str) -> bool {
+    let endpoint = |url: &str| -> Option<(String, u16, String)> {
+        let url = url::Url::parse(url).ok()?;
+        let host = url.host_str()?.trim_matches(['[', ']']).to_lowercase();
+        let port = url.port().unwrap_or(6379);
+        let database = match url.path().trim_matches('/') {
+            "" => "0".to_string(),
+            database => database.to_string(),
+        };
+        Some((host, port, database))
+    };
+    let (Some((host_a, port_a, db_a)), Some((host_b, port_b, db_b))) = (endpoint(a), endpoint(b))
+    else {
+        return false;
+    };
+    if port_a != port_b || db_a != db_b {
+        return false;
+    }
+    if host_a == host_b {
+        return true;
+    }
+    let addresses = |host: &str| -> Vec<IpAddr> {
+        (host, port_a)
+            .to_socket_addrs()
+            .map(|addrs| addrs.map(|addr| addr.ip()).collect())
+            .unwrap_or_default()
+    };
+    let resolved = addresses(&host_a);
+    addresses(&host_b).iter().any(|ip| {
+        resolved.contains(ip) || (ip.is_loopback() && resolved.iter().any(IpAddr::is_loopback))
+    })
+}
+
+fn run_statement(graph: &mut SyncGraph, statement: &str) -> Result<()> {
+    graph
+        .query(statement)
+        .execute()
+        .map(drop)
+        .map_err(|e| anyhow::anyhow!("Query failed: {e}"))
+}
+
+impl FalkorCli {
+    pub fn copy_graph(&self, options: &CopyOptions) -> Result<()> {
+        let remote = options
+            .to_url
+            .as_deref()
+            .map(|url| Self::connect(url, None))
+            .transpose()?;
+        let target = remote.as_ref().unwrap_or(&self.client);
+
+        let same = options
+            .to_url
+            .as_deref()
+            .is_none_or(|url| same_server(url, &self.connection_string));
+        if same && options.source == options.destination {
+            return Err(anyhow::anyhow!("Source and destination are the same graph"));
+        }
+        let sources = self
+            .client
+            .list_graphs()
+            .map_err(|e| anyhow::anyhow!("Failed to list graphs: {e}"))?;
+        if !sources.contains(&options.source) {
+            return Err(anyhow::anyhow!("Graph '{}' does not exist", options.source));
+        }
+        let targets = target
+            .list_graphs()
+            .map_err(|e| anyhow::anyhow!("Failed to list graphs: {e}"))?;
+        if targets.contains(&options.destination) {
+            if !options.overwrite {
+                return Err(anyhow::anyhow!(
+                    "Graph '{}' already exists. Use --overwrite to replace it",
+                    options.destination
+                ));
+            }
+            target
+                .select_graph(&options.destination)
+                .delete()
+                .map_err(|e| anyhow::anyhow!("Failed to delete '{}': {e}", options.destination))?;
+        }
+
+        let mut source = self.client.select_graph(&options.source);
+        let mut destination = target.select_graph(&options.destination);
+
+        // GRAPH.COPY always brings the indices along, so skipping them means streaming
+        if remote.is_none() && !options.skip_indices {
+            self.client
+                .copy_graph(&options.source, &options.destination)
+                .map_err(|e| anyhow::anyhow!("GRAPH.COPY failed: {e}"))?;
+        } else {
+            Self::stream_copy(&mut source, &mut destination, options)?;
+        }
+
+        println!(
+            "{} '{}' -> '{}'",
+            "Copied graph".green().bold(),
+            options.source,
+            options.destination
+        );
+
+        if options.verify {
+            for kind in [EntityKind::Nodes, EntityKind::Relationships] {
+                let expected = count_entities(&mut source, kind)?;
+                let actual = count_entities(&mut destination, kind)?;
+                if expected != actual {
+                    return Err(anyhow::anyhow!(
+                        "Verification failed: source has {expected} {}, destination has {actual}",
+                        kind.name()
+                    ));
+                }
+                println!("  {}: {actual} (verified)", kind.name());
+            }
+        }
+        Ok(())
+    }
+
+    /// Recreates the source graph on the destination with batched writes.
+    fn stream_copy(
+        source: &mut SyncGraph,
+        destination: &mut SyncGraph,
+        options: &CopyOptions,
+    ) -> Result<()> {
+        if !options.skip_indices {
+            let schema = read_schema(source)?;
+            for statement in &schema.index_statements {
+                run_statement(destination, statement)?;
+            }
+            for constraint in &schema.constraints {
+                apply_constraint(destination, constraint)?;
+            }
+        }
+        run_statement(destination, &import_index_statement())?;
+
+        for kind in [EntityKind::Nodes, EntityKind::Relationships] {
+            let count = stream_entities(
+                source,
+                kind,
+                options.batch_size,
+                options.page_size,
+                &mut |statement| run_statement(destination, &statement),
+            )?;
+            println!("  {}: {count} copied", kind.name());
+        }
+
+        for statement in cleanup_statements() {
+            run_statement(destination, &statement)?;
+        }
+        Ok(())
+    }
+}
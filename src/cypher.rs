@@ -0,0 +1,122 @@
+use falkordb::{Edge, FalkorValue, Node, Path};
+use std::collections::{BTreeMap, HashMap};
+
+use crate::client::value_to_string;
+use crate::dump::value_to_cypher;
+use crate::params::cypher_key;
+
+/// Renders a value for a table cell the way Cypher writes it: nodes as
+/// `(:Person {name: "Alice"})`, relationships as `[:KNOWS {since: 2020}]`,
+/// paths as `(a)-[:R]->(b)` chains, and lists and maps as literals. Property
+/// values longer than `limit` characters are cut with `…`. Top-level strings
+/// and numbers are shown as they are.
+pub fn display_value(value: &FalkorValue, limit: Option<usize>) -> String {
+    match value {
+        FalkorValue::Node(node) => node_pattern(node, limit),
+        FalkorValue::Edge(edge) => format!("[{}]", relationship_pattern(edge, limit)),
+        FalkorValue::Path(path) => path_pattern(path, limit),
+        FalkorValue::Array(_)
+        | FalkorValue::Map(_)
+        | FalkorValue::Point(_)
+        | FalkorValue::Vec32(_) => literal(value, limit),
+        value => value_to_string(value),
+    }
+}
+
+/// A node with its labels and properties, e.g. `(:Person {age: 30})`.
+fn node_pattern(node: &Node, limit: Option<usize>) -> String {
+    let labels: String = node
+        .labels
+        .iter()
+        .map(|label| format!(":{}", cypher_key(label)))
+        .collect();
+    let properties = properties(&node.properties, limit);
+    match (labels.is_empty(), properties.is_empty()) {
+        (_, true) => format!("({labels})"),
+        (true, false) => format!("({properties})"),
+        (false, false) => format!("({labels} {properties})"),
+    }
+}
+
+/// The inside of a relationship pattern, e.g. `:KNOWS {since: 2020}`.
+fn relationship_pattern(edge: &Edge, limit: Option<usize>) -> String {
+    let properties = properties(&edge.properties, limit);
+    let relationship_type = cypher_key(&edge.relationship_type);
+    if properties.is_empty() {
+        format!(":{relationship_type}")
+    } else {
+        format!(":{relationship_type} {properties}")
+    }
+}
+
+/// Nodes joined by their relationships, pointing the way each relationship
+/// was traversed.
+fn path_pattern(path: &Path, limit: Option<usize>) -> String {
+    let mut text = String::new();
+    for (index, node) in path.nodes.iter().enumerate() {
+        if index > 0 {
+            if let Some(edge) = path.relationships.get(index - 1) {
+                let relationship = relationship_pattern(edge, limit);
+                if edge.dst_node_id == node.entity_id {
+                    text.push_str(&format!("-[{relationship}]->"));
+                } else {
+                    text.push_str(&format!("<-[{relationship}]-"));
+                }
+            }
+        }
+        text.push_str(&node_pattern(node, limit));
+    }
+    text
+}
+
+/// A property map sorted by key, or an empty string when there are none.
+fn properties(properties: &HashMap<String, FalkorValue>, limit: Option<usize>) -> String {
+    if properties.is_empty() {
+        return String::new();
+    }
+    let sorted: BTreeMap<&String, &FalkorValue> = properties.iter().collect();
+    let entries: Vec<String> = sorted
+        .into_iter()
+        .map(|(key, value)| format!("{}: {}", cypher_key(key), property_value(value, limit)))
+        .collect();
+    format!("{{{}}}", entries.join(", "))
+}
+
+/// A property value, cut to `limit` characters. Strings keep their closing
+/// quote when cut.
+fn property_value(value: &FalkorValue, limit: Option<usize>) -> String {
+    let Some(limit) = limit else {
+        return literal(value, None);
+    };
+    if let FalkorValue::String(text) = value {
+        if text.chars().count() > limit {
+            let cut: String = text.chars().take(limit).collect();
+            let quoted = serde_json::Value::String(cut).to_string();
+            return format!("{}…\"", &quoted[..quoted.len() - 1]);
+        }
+    }
+    let text = literal(value, Some(limit));
+    if text.chars().count() > limit {
+        let cut: String = text.chars().take(limit).collect();
+        format!("{cut}…")
+    } else {
+        text
+    }
+}
+
+/// A Cypher literal. Properties of entities and maps inside it are cut to
+/// `limit`.
+fn literal(value: &FalkorValue, limit: Option<usize>) -> String {
+    match value {
+        FalkorValue::Node(_) | FalkorValue::Edge(_) | FalkorValue::Path(_) => {
+            display_value(value, limit)
+        }
+        FalkorValue::Array(items) => {
+            let items: Vec<String> = items.iter().map(|item| literal(item, limit)).collect();
+            format!("[{}]", items.join(", "))
+        }
+        FalkorValue::Map(map) if map.is_empty() => "{}".to_string(),
+        FalkorValue::Map(map) => properties(map, limit),
+        value => value_to_cypher(value),
+    }
+}
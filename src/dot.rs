@@ -0,0 +1,151 @@
+use anyhow::Result;
+use falkordb::{Edge, FalkorValue, Node};
+use std::collections::BTreeSet;
+use std::io::Write;
+
+use crate::client::value_to_string;
+use crate::format::{OutputFormatter, ResultSummary};
+
+/// Fill colors for node labels (ColorBrewer Set3), picked by a hash of the
+/// label so a label keeps its color across queries.
+const PALETTE: [&str; 12] = [
+    "#8dd3c7", "#ffffb3", "#bebada", "#fb8072", "#80b1d3", "#fdb462", "#b3de69", "#fccde5",
+    "#d9d9d9", "#bc80bd", "#ccebc5", "#ffed6f",
+];
+
+/// Properties tried, in order, when no display property is chosen.
+const DEFAULT_DISPLAY_PROPERTIES: [&str; 2] = ["name", "title"];
+
+/// Color for a label, stable across runs (FNV-1a hash).
+pub fn label_color(label: &str) -> &'static str {
+    let hash = label.bytes().fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    });
+    #[allow(clippy::cast_possible_truncation)]
+    PALETTE[(hash % PALETTE.len() as u64) as usize]
+}
+
+/// Quotes a string for use as a DOT ID or attribute value.
+pub fn quote(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Text drawn inside a node: the display property if the node has it,
+/// otherwise its first label and ID.
+pub fn node_caption(node: &Node, display_property: Option<&str>) -> String {
+    let value = match display_property {
+        Some(property) => node.properties.get(property),
+        None => DEFAULT_DISPLAY_PROPERTIES
+            .iter()
+            .find_map(|property| node.properties.get(*property)),
+    };
+    value.map_or_else(
+        || match node.labels.first() {
+            Some(label) => format!("{label} {}", node.entity_id),
+            None => node.entity_id.to_string(),
+        },
+        value_to_string,
+    )
+}
+
+/// Writes the nodes, edges and paths of a result as a Graphviz digraph.
+/// Scalar columns are ignored, and each entity is drawn once however often it
+/// is returned.
+pub struct DotWriter {
+    display_property: Option<String>,
+    nodes: BTreeSet<i64>,
+    edges: BTreeSet<i64>,
+}
+
+impl OutputFormatter for DotWriter {
+    fn begin(&mut self, _summary: &ResultSummary, out: &mut dyn Write) -> Result<()> {
+        writeln!(out, "digraph result {{")?;
+        writeln!(
+            out,
+            "  node [shape=box, style=\"rounded,filled\", fontname=\"Helvetica\"];"
+        )?;
+        writeln!(out, "  edge [fontname=\"Helvetica\", fontsize=10];")?;
+        Ok(())
+    }
+
+    fn row(&mut self, row: &[FalkorValue], out: &mut dyn Write) -> Result<()> {
+        for value in row {
+            self.write_value(value, out)?;
+        }
+        Ok(())
+    }
+
+    fn end(&mut self, out: &mut dyn Write) -> Result<()> {
+        writeln!(out, "}}")?;
+        Ok(())
+    }
+}
+
+impl DotWriter {
+    pub const fn new(display_property: Option<String>) -> Self {
+        Self {
+            display_property,
+            nodes: BTreeSet::new(),
+            edges: BTreeSet::new(),
+        }
+    }
+
+    fn write_value(&mut self, value: &FalkorValue, out: &mut dyn Write) -> Result<()> {
+        match value {
+            FalkorValue::Node(node) => self.write_node(node, out)?,
+            FalkorValue::Edge(edge) => self.write_edge(edge, out)?,
+            FalkorValue::Path(path) => {
+                for node in &path.nodes {
+                    self.write_node(node, out)?;
+                }
+                for edge in &path.relationships {
+                    self.write_edge(edge, out)?;
+                }
+            }
+            FalkorValue::Array(items) => {
+                for item in items {
+                    self.write_value(item, out)?;
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn write_node(&mut self, node: &Node, out: &mut dyn Write) -> Result<()> {
+        if !self.nodes.insert(node.entity_id) {
+            return Ok(());
+        }
+        let labels: String = node
+            .labels
+            .iter()
+            .map(|label| format!(":{label}"))
+            .collect();
+        let color = node
+            .labels
+            .first()
+            .map_or("#ffffff", |label| label_color(label));
+        writeln!(
+            out,
+            "  n{} [label={}, fillcolor=\"{color}\", tooltip={}];",
+            node.entity_id,
+            quote(&node_caption(node, self.display_property.as_deref())),
+            quote(&labels)
+        )?;
+        Ok(())
+    }
+
+    fn write_edge(&mut self, edge: &Edge, out: &mut dyn Write) -> Result<()> {
+        if !self.edges.insert(edge.entity_id) {
+            return Ok(());
+        }
+        writeln!(
+            out,
+            "  n{} -> n{} [label={}];",
+            edge.src_node_id,
+            edge.dst_node_id,
+            quote(&edge.relationship_type)
+        )?;
+        Ok(())
+    }
+}
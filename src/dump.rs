@@ -0,0 +1,401 @@
+use anyhow::Result;
+use falkordb::{
+    Constraint, ConstraintStatus, ConstraintType, Edge, EntityType, FalkorIndex, FalkorValue,
+    IndexType, Node, SyncGraph,
+};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::io::{self, BufWriter, Write};
+
+use crate::client::{value_to_json, FalkorCli};
+use crate::export::{scan_entities, EntityKind};
+use crate::import::escape_name;
+use crate::output;
+use crate::params;
+
+/// Label and property added to every node while a dump is replayed, so
+/// relationships can find their endpoints. Both are removed at the end.
+pub const IMPORT_LABEL: &str = "__Import";
+pub const IMPORT_ID: &str = "__import_id";
+
+/// Renders a value as a Cypher literal that recreates it, including points and
+/// vectors, which have no JSON equivalent.
+pub fn value_to_cypher(value: &FalkorValue) -> String {
+    match value {
+        FalkorValue::Point(point) => format!(
+            "point({{latitude: {}, longitude: {}}})",
+            point.latitude, point.longitude
+        ),
+        FalkorValue::Vec32(vec) => format!(
+            "vecf32({})",
+            params::json_to_cypher(&serde_json::json!(vec.values))
+        ),
+        FalkorValue::Array(items) => {
+            let items: Vec<String> = items.iter().map(value_to_cypher).collect();
+            format!("[{}]", items.join(", "))
+        }
+        FalkorValue::Map(map) => properties_to_cypher(map, None),
+        value => params::json_to_cypher(&value_to_json(value)),
+    }
+}
+
+/// Renders a property map, sorted by key, optionally starting with the
+/// temporary import ID.
+pub fn properties_to_cypher(
+    properties: &HashMap<String, FalkorValue>,
+    import_id: Option<i64>,
+) -> String {
+    let sorted: BTreeMap<&String, &FalkorValue> = properties.iter().collect();
+    let entries: Vec<String> = import_id
+        .map(|id| format!("{IMPORT_ID}: {id}"))
+        .into_iter()
+        .chain(
+            sorted
+                .into_iter()
+                .map(|(key, value)| format!("{}: {}", escape_name(key), value_to_cypher(value))),
+        )
+        .collect();
+    format!("{{{}}}", entries.join(", "))
+}
+
+/// Pattern that recreates a node, e.g. ``(:`Person`:__Import {__import_id: 3, `name`: "Ann"})``.
+pub fn node_pattern(node: &Node) -> String {
+    let labels: String = node
+        .labels
+        .iter()
+        .map(|label| format!(":{}", escape_name(label)))
+        .collect();
+    format!(
+        "({labels}:{IMPORT_LABEL} {})",
+        properties_to_cypher(&node.properties, Some(node.entity_id))
+    )
+}
+
+/// Cypher DDL recreating an index. Fields covered by a unique constraint are
+/// left out, since creating the constraint creates their index.
+pub fn index_statements(
+    index: &FalkorIndex,
+    constrained: &BTreeSet<(String, String)>,
+) -> Vec<String> {
+    let (pattern, var) = match index.entity_type {
+        EntityType::Node => (format!("(n:{})", escape_name(&index.index_label)), "n"),
+        EntityType::Edge => (
+            format!("()-[e:{}]-()", escape_name(&index.index_label)),
+            "e",
+        ),
+    };
+    let fields_of = |kind: IndexType| -> Vec<&String> {
+        index
+            .fields
+            .iter()
+            .filter(|field| {
+                index
+                    .field_types
+                    .get(*field)
+                    .is_some_and(|types| types.contains(&kind))
+            })
+            .collect()
+    };
+    let on = |fields: &[&String]| -> String {
+        let fields: Vec<String> = fields
+            .iter()
+            .map(|field| format!("{var}.{}", escape_name(field)))
+            .collect();
+        fields.join(", ")
+    };
+
+    let mut statements = Vec::new();
+    let range: Vec<&String> = fields_of(IndexType::Range)
+        .into_iter()
+        .filter(|field| !constrained.contains(&(index.index_label.clone(), (*field).clone())))
+        .collect();
+    if !range.is_empty() {
+        statements.push(format!("CREATE INDEX FOR {pattern} ON ({})", on(&range)));
+    }
+    let fulltext = fields_of(IndexType::Fulltext);
+    if !fulltext.is_empty() {
+        statements.push(format!(
+            "CREATE FULLTEXT INDEX FOR {pattern} ON ({})",
+            on(&fulltext)
+        ));
+    }
+    for field in fields_of(IndexType::Vector) {
+        let options = if index.options.is_empty() {
+            String::new()
+        } else {
+            format!(" OPTIONS {}", properties_to_cypher(&index.options, None))
+        };
+        statements.push(format!(
+            "CREATE VECTOR INDEX FOR {pattern} ON ({}){options}",
+            on(&[field])
+        ));
+    }
+    statements
+}
+
+/// `\constraint` meta-command recreating a constraint; constraints have no
+/// Cypher syntax in `FalkorDB`. Names are quoted as in Cypher and read back
+/// with [`split_names`].
+pub fn constraint_command(constraint: &Constraint) -> String {
+    let kind = match constraint.constraint_type {
+        ConstraintType::Unique => "UNIQUE",
+        ConstraintType::Mandatory => "MANDATORY",
+    };
+    let entity = match constraint.entity_type {
+        EntityType::Node => "NODE",
+        EntityType::Edge => "RELATIONSHIP",
+    };
+    let properties: Vec<String> = constraint
+        .properties
+        .iter()
+        .map(|property| escape_name(property))
+        .collect();
+    format!(
+        "\\constraint {kind} {entity} {} {}",
+        escape_name(&constraint.label),
+        properties.join(" ")
+    )
+}
+
+/// Splits meta-command arguments at whitespace. A name in backticks may hold
+/// spaces, with a doubled backtick standing for one.
+pub fn split_names(text: &str) -> Result<Vec<String>> {
+    let mut names = Vec::new();
+    let mut chars = text.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+            continue;
+        }
+        let mut name = String::new();
+        if c == '`' {
+            chars.next();
+            loop {
+                match chars.next() {
+                    Some('`') if chars.peek() == Some(&'`') => {
+                        chars.next();
+                        name.push('`');
+                    }
+                    Some('`') => break,
+                    Some(c) => name.push(c),
+                    None => return Err(anyhow::anyhow!("Unterminated quoted name in '{text}'")),
+                }
+            }
+        } else {
+            while let Some(c) = chars.next_if(|c| !c.is_whitespace()) {
+                name.push(c);
+            }
+        }
+        names.push(name);
+    }
+    Ok(names)
+}
+
+/// Schema of a graph, read before copying or dumping it.
+pub struct SchemaDefinition {
+    /// Cypher statements recreating the indices
+    pub index_statements: Vec<String>,
+    pub constraints: Vec<Constraint>,
+}
+
+/// Reads the indices and constraints of a graph. Failed constraints are left
+/// out since they could not be recreated either.
+pub fn read_schema(graph: &mut SyncGraph) -> Result<SchemaDefinition> {
+    let constraints: Vec<Constraint> = graph
+        .list_constraints()
+        .map_err(|e| anyhow::anyhow!("Failed to list constraints: {e}"))?
+        .data
+        .into_iter()
+        .filter(|c| c.status != ConstraintStatus::Failed)
+        .collect();
+    let constrained: BTreeSet<(String, String)> = constraints
+        .iter()
+        .filter(|c| c.constraint_type == ConstraintType::Unique)
+        .flat_map(|c| c.properties.iter().map(|p| (c.label.clone(), p.clone())))
+        .collect();
+
+    let index_statements = graph
+        .list_indices()
+        .map_err(|e| anyhow::anyhow!("Failed to list indices: {e}"))?
+        .data
+        .iter()
+        .flat_map(|index| index_statements(index, &constrained))
+        .collect();
+
+    Ok(SchemaDefinition {
+        index_statements,
+        constraints,
+    })
+}
+
+/// Creates a constraint read by [`read_schema`] on another graph.
+pub fn apply_constraint(graph: &mut SyncGraph, constraint: &Constraint) -> Result<()> {
+    let properties: Vec<&str> = constraint.properties.iter().map(String::as_str).collect();
+    let result = match constraint.constraint_type {
+        ConstraintType::Unique => graph.create_unique_constraint(
+            constraint.entity_type,
+            constraint.label.clone(),
+            &properties,
+        ),
+        ConstraintType::Mandatory => graph.create_mandatory_constraint(
+            constraint.entity_type,
+            &constraint.label,
+            &properties,
+        ),
+    };
+    result
+        .map(drop)
+        .map_err(|e| anyhow::anyhow!("Constraint creation failed: {e}"))
+}
+
+/// Statement indexing the temporary import IDs, run before any relationship
+/// is created.
+pub fn import_index_statement() -> String {
+    format!("CREATE INDEX FOR (n:{IMPORT_LABEL}) ON (n.{IMPORT_ID})")
+}
+
+/// Statements removing the temporary import label, IDs and their index.
+pub fn cleanup_statements() -> [String; 2] {
+    [
+        format!("DROP INDEX ON :{IMPORT_LABEL}({IMPORT_ID})"),
+        format!("MATCH (n:{IMPORT_LABEL}) SET n.{IMPORT_ID} = NULL REMOVE n:{IMPORT_LABEL}"),
+    ]
+}
+
+/// Reads every node or relationship of `graph` and passes statements that
+/// recreate them, `batch_size` entities at a time, to `emit`. Nodes must be
+/// emitted before relationships, which find their endpoints by import ID.
+/// Returns the number of entities read.
+pub fn stream_entities(
+    graph: &mut SyncGraph,
+    kind: EntityKind,
+    batch_size: usize,
+    page_size: u64,
+    emit: &mut dyn FnMut(String) -> Result<()>,
+) -> Result<u64> {
+    match kind {
+        EntityKind::Nodes => {
+            let mut batch: Vec<String> = Vec::with_capacity(batch_size);
+            let count = scan_entities(graph, kind, page_size, |value| {
+                if let FalkorValue::Node(node) = value {
+                    batch.push(node_pattern(&node));
+                    if batch.len() >= batch_size {
+                        emit(node_batch_statement(&batch))?;
+                        batch.clear();
+                    }
+                }
+                Ok(())
+            })?;
+            if !batch.is_empty() {
+                emit(node_batch_statement(&batch))?;
+            }
+            Ok(count)
+        }
+        EntityKind::Relationships => {
+            let mut batches: BTreeMap<String, Vec<String>> = BTreeMap::new();
+            let count = scan_entities(graph, kind, page_size, |value| {
+                if let FalkorValue::Edge(edge) = value {
+                    let batch = batches.entry(edge.relationship_type.clone()).or_default();
+                    batch.push(edge_row(&edge));
+                    if batch.len() >= batch_size {
+                        emit(edge_batch_statement(&edge.relationship_type, batch))?;
+                        batch.clear();
+                    }
+                }
+                Ok(())
+            })?;
+            for (rel_type, batch) in &batches {
+                if !batch.is_empty() {
+                    emit(edge_batch_statement(rel_type, batch))?;
+                }
+            }
+            Ok(count)
+        }
+    }
+}
+
+/// Row of a relationship batch: `[source import ID, target import ID, properties]`.
+fn edge_row(edge: &Edge) -> String {
+    format!(
+        "[{}, {}, {}]",
+        edge.src_node_id,
+        edge.dst_node_id,
+        properties_to_cypher(&edge.properties, None)
+    )
+}
+
+fn node_batch_statement(batch: &[String]) -> String {
+    format!("CREATE\n  {}", batch.join(",\n  "))
+}
+
+fn edge_batch_statement(rel_type: &str, batch: &[String]) -> String {
+    format!(
+        "UNWIND [\n  {}\n] AS row\n\
+         MATCH (a:{IMPORT_LABEL} {{{IMPORT_ID}: row[0]}}), (b:{IMPORT_LABEL} {{{IMPORT_ID}: row[1]}})\n\
+         CREATE (a)-[r:{}]->(b) SET r = row[2]",
+        batch.join(",\n  "),
+        escape_name(rel_type)
+    )
+}
+
+/// Settings for `dump`.
+pub struct DumpOptions {
+    pub graph: String,
+    pub batch_size: usize,
+    pub page_size: u64,
+}
+
+impl FalkorCli {
+    /// Writes a Cypher script recreating the graph to stdout.
+    pub fn dump_graph(&self, options: &DumpOptions) -> Result<()> {
+        let stdout = io::stdout();
+        let mut out = BufWriter::new(stdout.lock());
+        self.write_dump(options, &mut out)?;
+        out.flush()?;
+        Ok(())
+    }
+
+    fn write_dump(&self, options: &DumpOptions, out: &mut dyn Write) -> Result<()> {
+        let mut graph = self.client.select_graph(&options.graph);
+
+        writeln!(out, "// FalkorDB dump of graph '{}'", options.graph)?;
+        writeln!(out, "// Created {}", output::timestamp())?;
+        writeln!(
+            out,
+            "// Replay with: falkordb-cli -g <graph> -f <this file>"
+        )?;
+        writeln!(out)?;
+
+        let schema = read_schema(&mut graph)?;
+        writeln!(out, "// Indices and constraints")?;
+        for statement in &schema.index_statements {
+            writeln!(out, "{statement};")?;
+        }
+        for constraint in &schema.constraints {
+            writeln!(out, "{}", constraint_command(constraint))?;
+        }
+        writeln!(out, "{};", import_index_statement())?;
+
+        for (kind, title) in [
+            (EntityKind::Nodes, "Nodes"),
+            (EntityKind::Relationships, "Relationships"),
+        ] {
+            writeln!(out, "\n// {title}")?;
+            stream_entities(
+                &mut graph,
+                kind,
+                options.batch_size,
+                options.page_size,
+                &mut |statement| {
+                    writeln!(out, "{statement};")?;
+                    Ok(())
+                },
+            )?;
+        }
+
+        writeln!(out, "\n// Remove the temporary import label and IDs")?;
+        for statement in cleanup_statements() {
+            writeln!(out, "{statement};")?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,56 @@
+use anyhow::{Context, Result};
+use std::env;
+use std::fs;
+use std::io::Write;
+use std::process::Command;
+
+use crate::output;
+
+#[cfg(windows)]
+const DEFAULT_EDITOR: &str = "notepad";
+#[cfg(not(windows))]
+const DEFAULT_EDITOR: &str = "vi";
+
+/// Resolves the editor command from `$VISUAL`, then `$EDITOR`, falling back to
+/// a platform default. Blank variables are skipped.
+pub fn editor_command(env: impl Fn(&str) -> Option<String>) -> String {
+    ["VISUAL", "EDITOR"]
+        .iter()
+        .filter_map(|var| env(var))
+        .find(|cmd| !cmd.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_EDITOR.to_string())
+}
+
+/// Opens `initial` in the user's editor through a temporary `.cypher` file and
+/// returns the buffer once the editor exits.
+pub fn edit_statement(initial: &str) -> Result<String> {
+    let (path, mut file) = output::create_temp_file("falkordb-cli", ".cypher")?;
+    let written = file
+        .write_all(initial.as_bytes())
+        .and_then(|()| file.sync_all());
+    drop(file);
+    if let Err(e) = written {
+        let _ = fs::remove_file(&path);
+        return Err(e).with_context(|| format!("Failed to write temp file '{}'", path.display()));
+    }
+
+    let command = editor_command(|var| env::var(var).ok());
+    let mut parts = command.split_whitespace();
+    let program = parts
+        .next()
+        .ok_or_else(|| anyhow::anyhow!("Empty editor command"))?;
+
+    let status = Command::new(program)
+        .args(parts)
+        .arg(&path)
+        .status()
+        .with_context(|| format!("Failed to launch editor '{command}'"));
+
+    let buffer = fs::read_to_string(&path);
+    let _ = fs::remove_file(&path);
+
+    match status? {
+        s if s.success() => Ok(buffer?),
+        s => Err(anyhow::anyhow!("Editor exited with {s}")),
+    }
+}
@@ -0,0 +1,583 @@
+use anyhow::{Context, Result};
+use colored::Colorize;
+use falkordb::{FalkorValue, SyncGraph};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use crate::client::{edge_to_json, node_to_json, value_to_json, FalkorCli};
+use crate::columnar::{ColumnarFormat, ColumnarWriter};
+use crate::output;
+use crate::progress::Progress;
+
+/// Which kind of graph entity to scan.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EntityKind {
+    Nodes,
+    Relationships,
+}
+
+impl EntityKind {
+    /// Pattern binding the entity to `e`.
+    pub const fn pattern(self) -> &'static str {
+        match self {
+            Self::Nodes => "(e)",
+            Self::Relationships => "()-[e]->()",
+        }
+    }
+
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Nodes => "nodes",
+            Self::Relationships => "relationships",
+        }
+    }
+
+    /// Query returning the entities in one page of node IDs. Relationships
+    /// are paged by the ID of their source node, since FalkorDB can seek
+    /// nodes by ID but not relationships, so each page expands only the
+    /// outgoing relationships of its nodes and every relationship is read
+    /// exactly once.
+    pub fn page_query(self, from: u64, to: u64) -> String {
+        match self {
+            Self::Nodes => format!("MATCH (e) WHERE ID(e) >= {from} AND ID(e) < {to} RETURN e"),
+            Self::Relationships => {
+                format!("MATCH (s)-[e]->() WHERE ID(s) >= {from} AND ID(s) < {to} RETURN e")
+            }
+        }
+    }
+}
+
+/// Visits every node or relationship of a graph, fetching them in ranges of
+/// `page_size` node IDs so no single query has to return the whole graph.
+/// Returns the number of entities visited.
+pub fn scan_entities(
+    graph: &mut SyncGraph,
+    kind: EntityKind,
+    page_size: u64,
+    mut visit: impl FnMut(FalkorValue) -> Result<()>,
+) -> Result<u64> {
+    let max_id = graph
+        .ro_query("MATCH (n) RETURN max(ID(n))")
+        .execute()
+        .map_err(|e| anyhow::anyhow!("Query failed: {e}"))?
+        .data
+        .next()
+        .and_then(|row| row.into_iter().next());
+    let Some(FalkorValue::I64(max_id)) = max_id else {
+        // An empty graph
+        return Ok(0);
+    };
+    let end = u64::try_from(max_id).unwrap_or(0) + 1;
+
+    let mut progress = Progress::new(&format!("Reading {}", kind.name()), Some(end));
+    let mut visited = 0;
+    let mut from = 0;
+    while from < end {
+        let to = from.saturating_add(page_size);
+        let query = kind.page_query(from, to);
+        let result = graph
+            .ro_query(&query)
+            .execute()
+            .map_err(|e| anyhow::anyhow!("Query failed: {e}"))?;
+        for row in result.data {
+            if let Some(value) = row.into_iter().next() {
+                visit(value)?;
+                visited += 1;
+            }
+        }
+        from = to;
+        progress.set(from);
+    }
+    progress.finish();
+
+    Ok(visited)
+}
+
+/// Column type inferred from the values observed for a property, written to
+/// the header as `name:TYPE` in the same notation `import csv` reads.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ColumnType {
+    Int,
+    Float,
+    Boolean,
+    String,
+}
+
+/// Type of a property column, merged over every value seen.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ObservedType {
+    kind: Option<ColumnType>,
+    array: bool,
+}
+
+impl ObservedType {
+    /// Type of the values, or of the array elements; `None` until a non-empty
+    /// value has been seen.
+    pub const fn kind(self) -> Option<ColumnType> {
+        self.kind
+    }
+
+    pub const fn is_array(self) -> bool {
+        self.array
+    }
+
+    pub fn observe(&mut self, value: &serde_json::Value) {
+        let (kind, array) = match value {
+            serde_json::Value::Array(items) => {
+                let mut element = Self::default();
+                for item in items {
+                    if item.is_array() || item.is_object() {
+                        element.kind = Some(ColumnType::String);
+                    } else {
+                        element.observe(item);
+                    }
+                }
+                (element.kind, true)
+            }
+            value => (Some(scalar_type(value)), false),
+        };
+
+        if self.kind.is_some() && self.array != array {
+            // Arrays mixed with scalars can only be written as JSON text
+            self.kind = Some(ColumnType::String);
+            self.array = false;
+            return;
+        }
+        self.array = array;
+        self.kind = match (self.kind, kind) {
+            (None, kind) | (kind, None) => kind,
+            (Some(a), Some(b)) if a == b => Some(a),
+            (
+                Some(ColumnType::Int | ColumnType::Float),
+                Some(ColumnType::Int | ColumnType::Float),
+            ) => Some(ColumnType::Float),
+            _ => Some(ColumnType::String),
+        };
+    }
+
+    /// Header suffix for this type, e.g. `:INT[]`. Plain strings have none.
+    pub fn suffix(self) -> String {
+        let kind = match self.kind.unwrap_or(ColumnType::String) {
+            ColumnType::Int => "INT",
+            ColumnType::Float => "FLOAT",
+            ColumnType::Boolean => "BOOLEAN",
+            ColumnType::String if !self.array => return String::new(),
+            ColumnType::String => "STRING",
+        };
+        format!(":{kind}{}", if self.array { "[]" } else { "" })
+    }
+
+    /// GraphML `attr.type` for this type. Arrays have no GraphML type and are
+    /// written as JSON strings.
+    pub const fn graphml_type(self) -> &'static str {
+        match (self.kind, self.array) {
+            (Some(ColumnType::Int), false) => "long",
+            (Some(ColumnType::Float), false) => "double",
+            (Some(ColumnType::Boolean), false) => "boolean",
+            _ => "string",
+        }
+    }
+
+    /// Formats a value for a column of this type. Arrays are joined with `;`;
+    /// anything that does not fit the column is written as JSON.
+    pub fn format(self, value: &serde_json::Value) -> String {
+        match value {
+            serde_json::Value::Null => String::new(),
+            serde_json::Value::String(s) => s.clone(),
+            serde_json::Value::Array(items) if self.array => items
+                .iter()
+                .map(|item| self.element().format(item))
+                .collect::<Vec<_>>()
+                .join(";"),
+            value => value.to_string(),
+        }
+    }
+
+    /// Type of the elements of an array column.
+    pub const fn element(self) -> Self {
+        Self {
+            kind: self.kind,
+            array: false,
+        }
+    }
+}
+
+fn scalar_type(value: &serde_json::Value) -> ColumnType {
+    match value {
+        serde_json::Value::Number(n) if n.is_f64() => ColumnType::Float,
+        serde_json::Value::Number(_) => ColumnType::Int,
+        serde_json::Value::Bool(_) => ColumnType::Boolean,
+        _ => ColumnType::String,
+    }
+}
+
+/// Turns a label or relationship type into a safe file name stem.
+pub fn file_stem(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// IDs and properties of one spooled entity.
+type SpooledRow = (
+    Vec<serde_json::Value>,
+    serde_json::Map<String, serde_json::Value>,
+);
+
+/// File format of `export csv`, `export parquet` and `export arrow`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TableFormat {
+    Csv,
+    Columnar(ColumnarFormat),
+}
+
+impl TableFormat {
+    pub const fn extension(self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::Columnar(format) => format.extension(),
+        }
+    }
+}
+
+/// Rows of one output file, spooled to disk while the graph is scanned because
+/// the header needs every property seen in the file.
+struct TableGroup {
+    /// Labels of the nodes, or the relationship type
+    name: String,
+    spool_path: PathBuf,
+    spool: BufWriter<File>,
+    columns: BTreeMap<String, ObservedType>,
+    count: u64,
+}
+
+impl TableGroup {
+    fn new(name: &str) -> Result<Self> {
+        let (spool_path, spool) = output::create_temp_file("falkordb-cli", ".spool")?;
+        let spool = BufWriter::new(spool);
+        Ok(Self {
+            name: name.to_string(),
+            spool_path,
+            spool,
+            columns: BTreeMap::new(),
+            count: 0,
+        })
+    }
+
+    fn push(
+        &mut self,
+        ids: serde_json::Value,
+        properties: &serde_json::Map<String, serde_json::Value>,
+    ) -> Result<()> {
+        for (key, value) in properties {
+            self.columns.entry(key.clone()).or_default().observe(value);
+        }
+        serde_json::to_writer(&mut self.spool, &serde_json::json!([ids, properties]))?;
+        self.spool.write_all(b"\n")?;
+        self.count += 1;
+        Ok(())
+    }
+
+    /// Writes the output file with columns built from the observed
+    /// properties. The spool is removed when the group is dropped.
+    fn finish(
+        mut self,
+        path: &Path,
+        kind: EntityKind,
+        format: TableFormat,
+    ) -> Result<serde_json::Value> {
+        self.spool.flush()?;
+        let id_headers: &[&str] = match (format, kind) {
+            (TableFormat::Csv, EntityKind::Nodes) => &["_id:ID"],
+            (TableFormat::Csv, EntityKind::Relationships) => {
+                &["_id:IGNORE", ":START_ID", ":END_ID"]
+            }
+            (TableFormat::Columnar(_), EntityKind::Nodes) => &["_id"],
+            (TableFormat::Columnar(_), EntityKind::Relationships) => &["_id", "_src", "_dst"],
+        };
+        let spooled = BufReader::new(File::open(&self.spool_path)?)
+            .lines()
+            .map(|line| -> Result<SpooledRow> { Ok(serde_json::from_str(&line?)?) });
+
+        let header: Vec<String> = match format {
+            TableFormat::Csv => {
+                let header: Vec<String> = id_headers
+                    .iter()
+                    .map(|h| (*h).to_string())
+                    .chain(
+                        self.columns
+                            .iter()
+                            .map(|(key, kind)| format!("{key}{}", kind.suffix())),
+                    )
+                    .collect();
+                let mut writer = csv::Writer::from_path(path)?;
+                writer.write_record(&header)?;
+                for row in spooled {
+                    let (ids, properties) = row?;
+                    let record =
+                        ids.iter()
+                            .map(ToString::to_string)
+                            .chain(self.columns.iter().map(|(key, kind)| {
+                                properties
+                                    .get(key)
+                                    .map(|value| kind.format(value))
+                                    .unwrap_or_default()
+                            }));
+                    writer.write_record(record)?;
+                }
+                writer.flush()?;
+                header
+            }
+            TableFormat::Columnar(columnar) => {
+                let header: Vec<String> = id_headers
+                    .iter()
+                    .map(|h| (*h).to_string())
+                    .chain(self.columns.keys().cloned())
+                    .collect();
+                let mut id_type = ObservedType::default();
+                id_type.observe(&serde_json::json!(0));
+                let types = std::iter::repeat_n(id_type, id_headers.len())
+                    .chain(self.columns.values().copied())
+                    .collect();
+                let file = File::create(path)
+                    .with_context(|| format!("Failed to create '{}'", path.display()))?;
+                let mut writer = ColumnarWriter::new(
+                    columnar,
+                    BufWriter::new(file),
+                    header.clone(),
+                    Some(types),
+                );
+                for row in spooled {
+                    let (mut ids, properties) = row?;
+                    ids.extend(
+                        self.columns
+                            .keys()
+                            .map(|key| properties.get(key).cloned().unwrap_or_default()),
+                    );
+                    writer.push(ids)?;
+                }
+                writer.finish()?;
+                header
+            }
+        };
+        Ok(serde_json::json!({
+            "file": path.file_name().map(|name| name.to_string_lossy()),
+            "rows": self.count,
+            "columns": header,
+        }))
+    }
+}
+
+impl Drop for TableGroup {
+    fn drop(&mut self) {
+        // Also reached when the export failed part way
+        let _ = fs::remove_file(&self.spool_path);
+    }
+}
+
+/// Settings for `export csv`, `export parquet` and `export arrow`.
+pub struct TableExportOptions {
+    pub graph: String,
+    pub out: PathBuf,
+    pub page_size: u64,
+    pub format: TableFormat,
+}
+
+impl FalkorCli {
+    /// Writes one file per label set and relationship type, plus a manifest.
+    pub fn export_tables(&self, options: &TableExportOptions) -> Result<()> {
+        fs::create_dir_all(&options.out)
+            .with_context(|| format!("Failed to create '{}'", options.out.display()))?;
+        let mut graph = self.client.select_graph(&options.graph);
+
+        let mut node_groups: BTreeMap<Vec<String>, TableGroup> = BTreeMap::new();
+        scan_entities(&mut graph, EntityKind::Nodes, options.page_size, |value| {
+            let FalkorValue::Node(node) = value else {
+                return Ok(());
+            };
+            let mut labels = node.labels.clone();
+            labels.sort();
+            if !node_groups.contains_key(&labels) {
+                let group = TableGroup::new(&labels.join(":"))?;
+                node_groups.insert(labels.clone(), group);
+            }
+            let properties = json_properties(&node.properties);
+            node_groups.get_mut(&labels).map_or(Ok(()), |group| {
+                group.push(serde_json::json!([node.entity_id]), &properties)
+            })
+        })?;
+
+        let mut edge_groups: BTreeMap<String, TableGroup> = BTreeMap::new();
+        scan_entities(
+            &mut graph,
+            EntityKind::Relationships,
+            options.page_size,
+            |value| {
+                let FalkorValue::Edge(edge) = value else {
+                    return Ok(());
+                };
+                if !edge_groups.contains_key(&edge.relationship_type) {
+                    let group = TableGroup::new(&edge.relationship_type)?;
+                    edge_groups.insert(edge.relationship_type.clone(), group);
+                }
+                let properties = json_properties(&edge.properties);
+                edge_groups
+                    .get_mut(&edge.relationship_type)
+                    .map_or(Ok(()), |group| {
+                        group.push(
+                            serde_json::json!([edge.entity_id, edge.src_node_id, edge.dst_node_id]),
+                            &properties,
+                        )
+                    })
+            },
+        )?;
+
+        let mut used = BTreeSet::new();
+        let mut nodes = Vec::new();
+        for (labels, group) in node_groups {
+            let path = unique_path(&options.out, &group.name, options.format, &mut used);
+            let name = group.name.clone();
+            let mut entry = group.finish(&path, EntityKind::Nodes, options.format)?;
+            entry["labels"] = serde_json::json!(labels);
+            println!("  {name}: {} nodes -> {}", entry["rows"], path.display());
+            nodes.push(entry);
+        }
+        let mut relationships = Vec::new();
+        for (rel_type, group) in edge_groups {
+            let path = unique_path(&options.out, &rel_type, options.format, &mut used);
+            let mut entry = group.finish(&path, EntityKind::Relationships, options.format)?;
+            entry["type"] = serde_json::json!(rel_type);
+            println!(
+                "  {rel_type}: {} relationships -> {}",
+                entry["rows"],
+                path.display()
+            );
+            relationships.push(entry);
+        }
+
+        let manifest = serde_json::json!({
+            "graph": options.graph,
+            "exported_at": output::timestamp(),
+            "nodes": nodes,
+            "relationships": relationships,
+        });
+        let manifest_path = options.out.join("manifest.json");
+        fs::write(
+            &manifest_path,
+            serde_json::to_string_pretty(&manifest)? + "\n",
+        )?;
+
+        println!(
+            "{} {} node files and {} relationship files written to {}",
+            "Export complete:".green().bold(),
+            nodes.len(),
+            relationships.len(),
+            options.out.display()
+        );
+        Ok(())
+    }
+}
+
+fn json_properties(
+    properties: &std::collections::HashMap<String, FalkorValue>,
+) -> serde_json::Map<String, serde_json::Value> {
+    properties
+        .iter()
+        .map(|(key, value)| (key.clone(), value_to_json(value)))
+        .collect()
+}
+
+/// Picks `<stem>.<extension>` in `dir`, adding a numeric suffix when two names
+/// map to the same stem.
+fn unique_path(
+    dir: &Path,
+    name: &str,
+    format: TableFormat,
+    used: &mut BTreeSet<String>,
+) -> PathBuf {
+    let stem = if name.is_empty() {
+        "unlabeled".to_string()
+    } else {
+        file_stem(name)
+    };
+    let mut candidate = stem.clone();
+    let mut n = 2;
+    while !used.insert(candidate.clone()) {
+        candidate = format!("{stem}_{n}");
+        n += 1;
+    }
+    dir.join(format!("{candidate}.{}", format.extension()))
+}
+
+/// Settings for `export jsonl`.
+pub struct JsonlExportOptions {
+    pub graph: String,
+    /// Output file; stdout when unset
+    pub out: Option<PathBuf>,
+    pub page_size: u64,
+}
+
+/// A node or edge as a JSON Lines record: the `--format json` representation
+/// tagged with `"type": "node"` or `"type": "edge"`. Vector properties are
+/// written as `{"vecf32": [...]}` so they can be told apart from lists.
+pub fn jsonl_record(value: &FalkorValue) -> Option<serde_json::Value> {
+    let (mut record, kind, properties) = match value {
+        FalkorValue::Node(node) => (node_to_json(node), "node", &node.properties),
+        FalkorValue::Edge(edge) => (edge_to_json(edge), "edge", &edge.properties),
+        _ => return None,
+    };
+    for (key, value) in properties {
+        if let FalkorValue::Vec32(vector) = value {
+            record["properties"][key] = serde_json::json!({"vecf32": vector.values});
+        }
+    }
+    record["type"] = serde_json::json!(kind);
+    Some(record)
+}
+
+impl FalkorCli {
+    /// Streams every node, then every edge, as one JSON object per line.
+    pub fn export_jsonl(&self, options: &JsonlExportOptions) -> Result<()> {
+        let mut out: Box<dyn Write> = match &options.out {
+            Some(path) => {
+                Box::new(BufWriter::new(File::create(path).with_context(|| {
+                    format!("Failed to create '{}'", path.display())
+                })?))
+            }
+            None => Box::new(BufWriter::new(io::stdout().lock())),
+        };
+        let mut graph = self.client.select_graph(&options.graph);
+
+        let mut counts = Vec::new();
+        for kind in [EntityKind::Nodes, EntityKind::Relationships] {
+            let count = scan_entities(&mut graph, kind, options.page_size, |value| {
+                if let Some(record) = jsonl_record(&value) {
+                    serde_json::to_writer(&mut out, &record)?;
+                    out.write_all(b"\n")?;
+                }
+                Ok(())
+            })?;
+            counts.push(count);
+        }
+        out.flush()?;
+
+        if let Some(path) = &options.out {
+            println!(
+                "{} {} nodes and {} relationships written to {}",
+                "Export complete:".green().bold(),
+                counts[0],
+                counts[1],
+                path.display()
+            );
+        }
+        Ok(())
+    }
+}
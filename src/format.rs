@@ -0,0 +1,567 @@
+use anyhow::Result;
+use clap::ValueEnum;
+use colored::Colorize;
+use falkordb::{FalkorValue, QueryResult};
+use std::io::Write;
+use std::time::Duration;
+
+use crate::client::{csv_escape, value_to_json, value_to_string};
+use crate::columnar::{ColumnarFormat, ColumnarFormatter};
+use crate::dot::DotWriter;
+use crate::table::{Border, Overflow, TableFormatter};
+use crate::theme::Theme;
+
+/// Header and statistics of a query result, without its rows.
+#[derive(Clone, Debug, Default)]
+pub struct ResultSummary {
+    pub header: Vec<String>,
+    /// Statistics as sent by the server, e.g. `Nodes created: 2`
+    pub stats: Vec<String>,
+    /// Time from sending the query to receiving the reply, measured by the
+    /// client
+    pub elapsed: Option<Duration>,
+}
+
+/// Renders a query result. `begin` is called once before the rows, `row`
+/// once per row as it is read, and `end` after the last row.
+pub trait OutputFormatter {
+    fn begin(&mut self, summary: &ResultSummary, out: &mut dyn Write) -> Result<()>;
+    fn row(&mut self, row: &[FalkorValue], out: &mut dyn Write) -> Result<()>;
+    fn end(&mut self, out: &mut dyn Write) -> Result<()>;
+}
+
+/// Settings shared by all formatters; each one reads what applies to it.
+#[derive(Clone, Debug, Default)]
+pub struct FormatOptions {
+    /// Node property used as the node caption in dot output
+    pub display_property: Option<String>,
+    /// Embed a stylesheet in html output
+    pub html_style: bool,
+    /// End ndjson output with a statistics line
+    pub ndjson_stats: bool,
+    /// Statistics shown above table output
+    pub stats: StatsMode,
+    /// Leave the statistics out of table output; they go to stderr instead
+    pub statistics_to_stderr: bool,
+    /// Colors of table headers and cells
+    pub theme: Theme,
+    /// Table border characters
+    pub border: Border,
+    /// How table cells wider than their column are shown
+    pub overflow: Overflow,
+    /// Terminal width tables are fitted to; unlimited when `None`
+    pub max_width: Option<usize>,
+    /// Entity property values in table cells are cut to this many characters
+    pub property_limit: Option<usize>,
+}
+
+/// A registered output format.
+pub struct FormatterInfo {
+    pub name: &'static str,
+    /// Written straight to the output as rows arrive instead of being
+    /// buffered for the pager
+    pub streaming: bool,
+    /// Binary output, which is never written to a terminal
+    pub binary: bool,
+    create: fn(&FormatOptions) -> Box<dyn OutputFormatter>,
+}
+
+impl FormatterInfo {
+    pub fn create(&self, options: &FormatOptions) -> Box<dyn OutputFormatter> {
+        (self.create)(options)
+    }
+}
+
+/// Every output format, in the order they are listed in `--help`.
+pub const FORMATTERS: &[FormatterInfo] = &[
+    FormatterInfo {
+        name: "table",
+        streaming: false,
+        binary: false,
+        create: |options| Box::new(TableFormatter::new(options)),
+    },
+    FormatterInfo {
+        name: "json",
+        streaming: false,
+        binary: false,
+        create: |_| Box::new(JsonFormatter::default()),
+    },
+    FormatterInfo {
+        name: "ndjson",
+        streaming: true,
+        binary: false,
+        create: |options| Box::new(NdjsonFormatter::new(options.ndjson_stats)),
+    },
+    FormatterInfo {
+        name: "csv",
+        streaming: false,
+        binary: false,
+        create: |_| Box::new(CsvFormatter),
+    },
+    FormatterInfo {
+        name: "dot",
+        streaming: false,
+        binary: false,
+        create: |options| Box::new(DotWriter::new(options.display_property.clone())),
+    },
+    FormatterInfo {
+        name: "markdown",
+        streaming: false,
+        binary: false,
+        create: |_| Box::new(MarkdownFormatter),
+    },
+    FormatterInfo {
+        name: "html",
+        streaming: false,
+        binary: false,
+        create: |options| Box::new(HtmlFormatter::new(options.html_style)),
+    },
+    FormatterInfo {
+        name: "parquet",
+        streaming: true,
+        binary: true,
+        create: |_| Box::new(ColumnarFormatter::new(ColumnarFormat::Parquet)),
+    },
+    FormatterInfo {
+        name: "arrow",
+        streaming: true,
+        binary: true,
+        create: |_| Box::new(ColumnarFormatter::new(ColumnarFormat::Arrow)),
+    },
+];
+
+/// Looks up a format by name.
+pub fn lookup(name: &str) -> Option<&'static FormatterInfo> {
+    FORMATTERS.iter().find(|info| info.name == name)
+}
+
+/// Names of all formats, for `--format` validation and error messages.
+pub fn names() -> Vec<&'static str> {
+    FORMATTERS.iter().map(|info| info.name).collect()
+}
+
+/// Feeds a result through a formatter. Returns the number of rows written.
+pub fn render<I>(
+    formatter: &mut dyn OutputFormatter,
+    result: QueryResult<I>,
+    elapsed: Option<Duration>,
+    out: &mut dyn Write,
+) -> Result<u64>
+where
+    I: Iterator<Item = Vec<FalkorValue>>,
+{
+    let summary = ResultSummary {
+        header: result.header,
+        stats: result.stats,
+        elapsed,
+    };
+    formatter.begin(&summary, out)?;
+    let mut rows = 0;
+    for row in result.data {
+        formatter.row(&row, out)?;
+        rows += 1;
+    }
+    formatter.end(out)?;
+    Ok(rows)
+}
+
+/// How much of the query statistics to show above table output.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum StatsMode {
+    /// No statistics
+    None,
+    /// One line with the non-zero counters and timings
+    Summary,
+    /// Every statistic the server sent, one per line
+    #[default]
+    Full,
+}
+
+/// Counters listed by `--stats full` even when the server left them out.
+const STANDARD_COUNTERS: [&str; 5] = [
+    "Nodes created",
+    "Nodes deleted",
+    "Relationships created",
+    "Relationships deleted",
+    "Properties set",
+];
+const EXECUTION_TIME: &str = "Query internal execution time";
+const CACHED_EXECUTION: &str = "Cached execution";
+
+/// Splits a statistic sent by the server, e.g. `Labels added: 1`, into its
+/// name and value. Timings lose their `milliseconds` unit.
+pub fn parse_statistic(line: &str) -> Option<(&str, &str)> {
+    let (name, value) = line.split_once(':')?;
+    let value = value.trim();
+    Some((
+        name.trim(),
+        value.strip_suffix("milliseconds").map_or(value, str::trim),
+    ))
+}
+
+/// Key of a statistic in JSON output, e.g. `labels_added`.
+fn statistic_key(name: &str) -> String {
+    if name == EXECUTION_TIME {
+        "query_time".to_string()
+    } else {
+        name.to_lowercase().replace(' ', "_")
+    }
+}
+
+fn milliseconds(elapsed: Duration) -> f64 {
+    elapsed.as_secs_f64() * 1000.0
+}
+
+/// Writes the statistics block shown above table output.
+pub fn write_statistics(
+    summary: &ResultSummary,
+    mode: StatsMode,
+    out: &mut dyn Write,
+) -> Result<()> {
+    let stats: Vec<(&str, &str)> = summary
+        .stats
+        .iter()
+        .filter_map(|line| parse_statistic(line))
+        .collect();
+    let server_time = stats
+        .iter()
+        .find(|(name, _)| *name == EXECUTION_TIME)
+        .and_then(|(_, value)| value.parse::<f64>().ok());
+
+    match mode {
+        StatsMode::None => {}
+        StatsMode::Summary => {
+            let counters: Vec<String> = stats
+                .iter()
+                .filter(|(name, value)| {
+                    ![EXECUTION_TIME, CACHED_EXECUTION].contains(name) && *value != "0"
+                })
+                .map(|(name, value)| format!("{name}: {value}"))
+                .collect();
+            let mut timings = Vec::new();
+            if let Some(time) = server_time {
+                timings.push(format!("{time:.3} ms server"));
+            }
+            if let Some(elapsed) = summary.elapsed {
+                timings.push(format!("{:.3} ms client", milliseconds(elapsed)));
+            }
+            let counters = if counters.is_empty() {
+                "No changes".to_string()
+            } else {
+                counters.join(", ")
+            };
+            if timings.is_empty() {
+                writeln!(out, "{}", counters.cyan())?;
+            } else {
+                writeln!(out, "{} ({})", counters.cyan(), timings.join(", "))?;
+            }
+            writeln!(out)?;
+        }
+        StatsMode::Full => {
+            writeln!(out, "{}", "Statistics:".cyan().bold())?;
+            for counter in STANDARD_COUNTERS {
+                let value = stats
+                    .iter()
+                    .find(|(name, _)| *name == counter)
+                    .map_or("0", |(_, value)| value);
+                writeln!(out, "  {counter}: {value}")?;
+            }
+            for (name, value) in &stats {
+                if !STANDARD_COUNTERS.contains(name) && *name != EXECUTION_TIME {
+                    writeln!(out, "  {name}: {value}")?;
+                }
+            }
+            if let Some(time) = server_time {
+                writeln!(
+                    out,
+                    "  Query internal execution time: {time:.3} milliseconds"
+                )?;
+            }
+            if let Some(elapsed) = summary.elapsed {
+                writeln!(
+                    out,
+                    "  Client wall time: {:.3} milliseconds",
+                    milliseconds(elapsed)
+                )?;
+            }
+            writeln!(out)?;
+        }
+    }
+    Ok(())
+}
+
+/// Plain values for shell scripts, used by `--raw`: one row per line with
+/// tab-separated values, and no header, statistics, colors or quoting. Nulls
+/// are empty.
+pub struct RawFormatter;
+
+impl OutputFormatter for RawFormatter {
+    fn begin(&mut self, _summary: &ResultSummary, _out: &mut dyn Write) -> Result<()> {
+        Ok(())
+    }
+
+    fn row(&mut self, row: &[FalkorValue], out: &mut dyn Write) -> Result<()> {
+        let fields: Vec<String> = row
+            .iter()
+            .map(|value| match value {
+                FalkorValue::None => String::new(),
+                value => value_to_string(value),
+            })
+            .collect();
+        writeln!(out, "{}", fields.join("\t"))?;
+        Ok(())
+    }
+
+    fn end(&mut self, _out: &mut dyn Write) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// One pretty-printed document holding the statistics, headers and rows.
+#[derive(Default)]
+pub struct JsonFormatter {
+    header: Vec<String>,
+    statistics: serde_json::Value,
+    data: Vec<serde_json::Value>,
+}
+
+impl OutputFormatter for JsonFormatter {
+    fn begin(&mut self, summary: &ResultSummary, _out: &mut dyn Write) -> Result<()> {
+        self.header.clone_from(&summary.header);
+        self.statistics = statistics_to_json(summary);
+        Ok(())
+    }
+
+    fn row(&mut self, row: &[FalkorValue], _out: &mut dyn Write) -> Result<()> {
+        self.data.push(row_to_json(&self.header, row));
+        Ok(())
+    }
+
+    fn end(&mut self, out: &mut dyn Write) -> Result<()> {
+        writeln!(
+            out,
+            "{}",
+            serde_json::to_string_pretty(&serde_json::json!({
+                "statistics": self.statistics,
+                "headers": self.header,
+                "data": self.data,
+            }))?
+        )?;
+        Ok(())
+    }
+}
+
+/// One compact JSON object per row, keyed by column name, optionally
+/// followed by a `{"statistics": {...}}` line.
+pub struct NdjsonFormatter {
+    stats: bool,
+    header: Vec<String>,
+    statistics: serde_json::Value,
+}
+
+impl NdjsonFormatter {
+    pub const fn new(stats: bool) -> Self {
+        Self {
+            stats,
+            header: Vec::new(),
+            statistics: serde_json::Value::Null,
+        }
+    }
+}
+
+impl OutputFormatter for NdjsonFormatter {
+    fn begin(&mut self, summary: &ResultSummary, _out: &mut dyn Write) -> Result<()> {
+        self.header.clone_from(&summary.header);
+        self.statistics = statistics_to_json(summary);
+        Ok(())
+    }
+
+    fn row(&mut self, row: &[FalkorValue], out: &mut dyn Write) -> Result<()> {
+        serde_json::to_writer(&mut *out, &row_to_json(&self.header, row))?;
+        writeln!(out)?;
+        Ok(())
+    }
+
+    fn end(&mut self, out: &mut dyn Write) -> Result<()> {
+        if self.stats {
+            serde_json::to_writer(
+                &mut *out,
+                &serde_json::json!({ "statistics": self.statistics }),
+            )?;
+            writeln!(out)?;
+        }
+        Ok(())
+    }
+}
+
+pub struct CsvFormatter;
+
+impl OutputFormatter for CsvFormatter {
+    fn begin(&mut self, summary: &ResultSummary, out: &mut dyn Write) -> Result<()> {
+        if !summary.header.is_empty() {
+            let header_line: Vec<String> = summary.header.iter().map(|h| csv_escape(h)).collect();
+            writeln!(out, "{}", header_line.join(","))?;
+        }
+        Ok(())
+    }
+
+    fn row(&mut self, row: &[FalkorValue], out: &mut dyn Write) -> Result<()> {
+        let fields: Vec<String> = row
+            .iter()
+            .map(|value| csv_escape(&value_to_string(value)))
+            .collect();
+        writeln!(out, "{}", fields.join(","))?;
+        Ok(())
+    }
+
+    fn end(&mut self, _out: &mut dyn Write) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// GitHub-flavored Markdown pipe table.
+pub struct MarkdownFormatter;
+
+impl OutputFormatter for MarkdownFormatter {
+    fn begin(&mut self, summary: &ResultSummary, out: &mut dyn Write) -> Result<()> {
+        let headers = &summary.header;
+        if !headers.is_empty() {
+            let header_line: Vec<String> = headers.iter().map(|h| markdown_escape(h)).collect();
+            writeln!(out, "| {} |", header_line.join(" | "))?;
+            writeln!(out, "|{}", " --- |".repeat(headers.len()))?;
+        }
+        Ok(())
+    }
+
+    fn row(&mut self, row: &[FalkorValue], out: &mut dyn Write) -> Result<()> {
+        let fields: Vec<String> = row
+            .iter()
+            .map(|value| markdown_escape(&value_to_string(value)))
+            .collect();
+        writeln!(out, "| {} |", fields.join(" | "))?;
+        Ok(())
+    }
+
+    fn end(&mut self, _out: &mut dyn Write) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Self-contained HTML table, optionally preceded by a stylesheet.
+pub struct HtmlFormatter {
+    style: bool,
+}
+
+impl HtmlFormatter {
+    pub const fn new(style: bool) -> Self {
+        Self { style }
+    }
+}
+
+impl OutputFormatter for HtmlFormatter {
+    fn begin(&mut self, summary: &ResultSummary, out: &mut dyn Write) -> Result<()> {
+        if self.style {
+            writeln!(out, "<style>{HTML_STYLE}</style>")?;
+        }
+        writeln!(out, "<table class=\"falkordb-result\">")?;
+        writeln!(out, "  <thead>")?;
+        write!(out, "    <tr>")?;
+        for header in &summary.header {
+            write!(out, "<th>{}</th>", html_escape(header))?;
+        }
+        writeln!(out, "</tr>")?;
+        writeln!(out, "  </thead>")?;
+        writeln!(out, "  <tbody>")?;
+        Ok(())
+    }
+
+    fn row(&mut self, row: &[FalkorValue], out: &mut dyn Write) -> Result<()> {
+        write!(out, "    <tr>")?;
+        for value in row {
+            write!(out, "<td>{}</td>", html_escape(&value_to_string(value)))?;
+        }
+        writeln!(out, "</tr>")?;
+        Ok(())
+    }
+
+    fn end(&mut self, out: &mut dyn Write) -> Result<()> {
+        writeln!(out, "  </tbody>")?;
+        writeln!(out, "</table>")?;
+        Ok(())
+    }
+}
+
+/// Query statistics as reported by `--format json` and `--format ndjson`:
+/// the standard counters, every other statistic the server sent, and the
+/// client wall time in milliseconds.
+pub fn statistics_to_json(summary: &ResultSummary) -> serde_json::Value {
+    let mut statistics = serde_json::Map::new();
+    for counter in STANDARD_COUNTERS {
+        statistics.insert(statistic_key(counter), serde_json::json!(0));
+    }
+    statistics.insert(statistic_key(EXECUTION_TIME), serde_json::json!(0.0));
+    for (name, value) in summary
+        .stats
+        .iter()
+        .filter_map(|line| parse_statistic(line))
+    {
+        let value = if name == EXECUTION_TIME {
+            value
+                .parse::<f64>()
+                .map_or_else(|_| value.into(), Into::into)
+        } else if let Ok(count) = value.parse::<i64>() {
+            count.into()
+        } else {
+            value
+                .parse::<f64>()
+                .map_or_else(|_| value.into(), Into::into)
+        };
+        statistics.insert(statistic_key(name), value);
+    }
+    if let Some(elapsed) = summary.elapsed {
+        statistics.insert(
+            "client_time".to_string(),
+            serde_json::json!(milliseconds(elapsed)),
+        );
+    }
+    serde_json::Value::Object(statistics)
+}
+
+/// A result row as a JSON object keyed by column name.
+pub fn row_to_json(header: &[String], row: &[FalkorValue]) -> serde_json::Value {
+    let record: serde_json::Map<String, serde_json::Value> = header
+        .iter()
+        .cloned()
+        .zip(row.iter().map(value_to_json))
+        .collect();
+    serde_json::Value::Object(record)
+}
+
+/// Stylesheet embedded before the table by `--format html --html-style`.
+const HTML_STYLE: &str = "table.falkordb-result { border-collapse: collapse; font-family: sans-serif; font-size: 14px; } \
+table.falkordb-result th, table.falkordb-result td { border: 1px solid #ccc; padding: 4px 8px; text-align: left; vertical-align: top; } \
+table.falkordb-result th { background: #f3f3f3; } \
+table.falkordb-result tbody tr:nth-child(even) { background: #fafafa; }";
+
+/// Escapes a cell for a GitHub-flavored Markdown table: pipes are escaped,
+/// HTML markup is shown as text, and line breaks become `<br>` so a cell
+/// never spans rows.
+pub fn markdown_escape(field: &str) -> String {
+    field
+        .replace('\\', "\\\\")
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('|', "\\|")
+        .replace("\r\n", "<br>")
+        .replace(['\n', '\r'], "<br>")
+}
+
+/// Escapes text for HTML element content and attribute values.
+pub fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
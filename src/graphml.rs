@@ -0,0 +1,576 @@
+use anyhow::{Context, Result};
+use colored::Colorize;
+use falkordb::{Edge, FalkorValue, Node, SyncGraph};
+use quick_xml::escape::escape;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::{Reader, XmlVersion};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter, Write};
+use std::path::PathBuf;
+
+use crate::client::{value_to_json, FalkorCli};
+use crate::export::{scan_entities, EntityKind, ObservedType};
+use crate::import::{JsonlRecord, RecordImporter};
+use crate::output;
+use crate::progress::Progress;
+
+/// Key holding node labels, as `:Label1:Label2`, following the convention of
+/// other graph database exporters.
+const LABELS_KEY: &str = "labels";
+/// Key holding the relationship type of an edge.
+const TYPE_KEY: &str = "label";
+/// Property names that would be read back as the labels or type, since
+/// readers match keys by `attr.name`.
+const RESERVED_NAMES: [&str; 2] = [LABELS_KEY, TYPE_KEY];
+/// Relationship type used for imported edges that have none.
+const DEFAULT_TYPE: &str = "RELATED";
+
+/// Property keys seen while writing elements, declared as GraphML `<key>`s
+/// once the whole graph has been written.
+#[derive(Default)]
+pub struct GraphmlKeys {
+    /// `(for, attr.name)` to key ID and observed type
+    keys: BTreeMap<(&'static str, String), (String, ObservedType)>,
+}
+
+impl GraphmlKeys {
+    fn key_id(&mut self, domain: &'static str, name: &str, value: &serde_json::Value) -> String {
+        let next = self.keys.len();
+        let (id, kind) = self
+            .keys
+            .entry((domain, name.to_string()))
+            .or_insert_with(|| (format!("d{next}"), ObservedType::default()));
+        kind.observe(value);
+        id.clone()
+    }
+
+    /// Writes the `<key>` declarations, including the label and type keys.
+    pub fn write(&self, out: &mut dyn Write) -> Result<()> {
+        writeln!(
+            out,
+            "  <key id=\"{LABELS_KEY}\" for=\"node\" attr.name=\"{LABELS_KEY}\" attr.type=\"string\"/>"
+        )?;
+        writeln!(
+            out,
+            "  <key id=\"{TYPE_KEY}\" for=\"edge\" attr.name=\"{TYPE_KEY}\" attr.type=\"string\"/>"
+        )?;
+        for ((domain, name), (id, kind)) in &self.keys {
+            writeln!(
+                out,
+                "  <key id=\"{id}\" for=\"{domain}\" attr.name=\"{}\" attr.type=\"{}\"/>",
+                escape(name.as_str()),
+                kind.graphml_type()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Text of a `<data>` element. Strings are written as-is, everything else as
+/// JSON, which is also the GraphML notation for numbers and booleans.
+fn data_text(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(s) => s.clone(),
+        value => value.to_string(),
+    }
+}
+
+fn write_data(
+    out: &mut dyn Write,
+    keys: &mut GraphmlKeys,
+    domain: &'static str,
+    properties: &HashMap<String, FalkorValue>,
+) -> Result<()> {
+    let sorted: BTreeMap<&String, &FalkorValue> = properties.iter().collect();
+    for (name, value) in sorted {
+        if RESERVED_NAMES.contains(&name.as_str()) {
+            return Err(anyhow::anyhow!(
+                "The {domain} property '{name}' clashes with the GraphML '{name}' key; \
+                 rename it before exporting"
+            ));
+        }
+        let value = value_to_json(value);
+        if value.is_null() {
+            continue;
+        }
+        let id = keys.key_id(domain, name, &value);
+        writeln!(
+            out,
+            "      <data key=\"{id}\">{}</data>",
+            escape(data_text(&value))
+        )?;
+    }
+    Ok(())
+}
+
+/// Writes a `<node>` element with its labels and properties.
+pub fn write_node(out: &mut dyn Write, keys: &mut GraphmlKeys, node: &Node) -> Result<()> {
+    writeln!(out, "    <node id=\"n{}\">", node.entity_id)?;
+    let labels: String = node
+        .labels
+        .iter()
+        .map(|label| format!(":{label}"))
+        .collect();
+    writeln!(
+        out,
+        "      <data key=\"{LABELS_KEY}\">{}</data>",
+        escape(labels)
+    )?;
+    write_data(out, keys, "node", &node.properties)?;
+    writeln!(out, "    </node>")?;
+    Ok(())
+}
+
+/// Writes an `<edge>` element with its type and properties.
+pub fn write_edge(out: &mut dyn Write, keys: &mut GraphmlKeys, edge: &Edge) -> Result<()> {
+    writeln!(
+        out,
+        "    <edge id=\"e{}\" source=\"n{}\" target=\"n{}\">",
+        edge.entity_id, edge.src_node_id, edge.dst_node_id
+    )?;
+    writeln!(
+        out,
+        "      <data key=\"{TYPE_KEY}\">{}</data>",
+        escape(edge.relationship_type.as_str())
+    )?;
+    write_data(out, keys, "edge", &edge.properties)?;
+    writeln!(out, "    </edge>")?;
+    Ok(())
+}
+
+/// Settings for `export graphml`.
+pub struct GraphmlExportOptions {
+    pub graph: String,
+    /// Output file; stdout when unset
+    pub out: Option<PathBuf>,
+    /// Cypher query whose returned nodes, edges and paths form the subgraph
+    pub query: Option<String>,
+    pub page_size: u64,
+}
+
+/// Tracks which nodes and edges of a filtered export were written, and which
+/// edge endpoints still need their node written.
+#[derive(Default)]
+struct Subgraph {
+    nodes: BTreeSet<i64>,
+    edges: BTreeSet<i64>,
+    endpoints: BTreeSet<i64>,
+}
+
+impl Subgraph {
+    fn write_value(
+        &mut self,
+        out: &mut dyn Write,
+        keys: &mut GraphmlKeys,
+        value: &FalkorValue,
+    ) -> Result<()> {
+        match value {
+            FalkorValue::Node(node) if self.nodes.insert(node.entity_id) => {
+                write_node(out, keys, node)?;
+            }
+            FalkorValue::Edge(edge) if self.edges.insert(edge.entity_id) => {
+                self.endpoints.insert(edge.src_node_id);
+                self.endpoints.insert(edge.dst_node_id);
+                write_edge(out, keys, edge)?;
+            }
+            FalkorValue::Path(path) => {
+                for node in &path.nodes {
+                    self.write_value(out, keys, &FalkorValue::Node(node.clone()))?;
+                }
+                for edge in &path.relationships {
+                    self.write_value(out, keys, &FalkorValue::Edge(edge.clone()))?;
+                }
+            }
+            FalkorValue::Array(items) => {
+                for item in items {
+                    self.write_value(out, keys, item)?;
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Nodes referenced by written edges but not returned by the query.
+    fn missing_endpoints(&self) -> Vec<i64> {
+        self.endpoints.difference(&self.nodes).copied().collect()
+    }
+}
+
+impl FalkorCli {
+    /// Writes the graph, or the subgraph returned by a query, as GraphML.
+    ///
+    /// Elements are spooled to a temporary file while the graph is read, since
+    /// the `<key>` declarations must come first but are only known at the end.
+    pub fn export_graphml(&self, options: &GraphmlExportOptions) -> Result<()> {
+        let mut graph = self.client.select_graph(&options.graph);
+        let (spool_path, spool_file) = output::create_temp_file("falkordb-cli", ".graphml")?;
+        let mut keys = GraphmlKeys::default();
+
+        let written = (|| -> Result<(u64, u64)> {
+            let mut spool = BufWriter::new(spool_file);
+            let counts = match &options.query {
+                Some(query) => Self::write_subgraph(
+                    &mut graph,
+                    query,
+                    options.page_size,
+                    &mut spool,
+                    &mut keys,
+                )?,
+                None => {
+                    let nodes =
+                        scan_entities(&mut graph, EntityKind::Nodes, options.page_size, |value| {
+                            match value {
+                                FalkorValue::Node(node) => write_node(&mut spool, &mut keys, &node),
+                                _ => Ok(()),
+                            }
+                        })?;
+                    let edges = scan_entities(
+                        &mut graph,
+                        EntityKind::Relationships,
+                        options.page_size,
+                        |value| match value {
+                            FalkorValue::Edge(edge) => write_edge(&mut spool, &mut keys, &edge),
+                            _ => Ok(()),
+                        },
+                    )?;
+                    (nodes, edges)
+                }
+            };
+            spool.flush()?;
+            drop(spool);
+
+            let mut out: Box<dyn Write> = match &options.out {
+                Some(path) => {
+                    Box::new(BufWriter::new(File::create(path).with_context(|| {
+                        format!("Failed to create '{}'", path.display())
+                    })?))
+                }
+                None => Box::new(BufWriter::new(io::stdout().lock())),
+            };
+            writeln!(out, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+            writeln!(
+                out,
+                r#"<graphml xmlns="http://graphml.graphdrawing.org/xmlns" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xsi:schemaLocation="http://graphml.graphdrawing.org/xmlns http://graphml.graphdrawing.org/xmlns/1.0/graphml.xsd">"#
+            )?;
+            keys.write(&mut out)?;
+            writeln!(
+                out,
+                "  <graph id=\"{}\" edgedefault=\"directed\">",
+                escape(options.graph.as_str())
+            )?;
+            io::copy(&mut File::open(&spool_path)?, &mut out)?;
+            writeln!(out, "  </graph>")?;
+            writeln!(out, "</graphml>")?;
+            out.flush()?;
+            Ok(counts)
+        })();
+        let _ = fs::remove_file(&spool_path);
+        let (nodes, edges) = written?;
+
+        if let Some(path) = &options.out {
+            println!(
+                "{} {nodes} nodes and {edges} edges written to {}",
+                "Export complete:".green().bold(),
+                path.display()
+            );
+        }
+        Ok(())
+    }
+
+    /// Writes every node, edge and path returned by `query`, then the nodes
+    /// at the ends of written edges that the query did not return.
+    fn write_subgraph(
+        graph: &mut SyncGraph,
+        query: &str,
+        page_size: u64,
+        out: &mut dyn Write,
+        keys: &mut GraphmlKeys,
+    ) -> Result<(u64, u64)> {
+        let mut subgraph = Subgraph::default();
+        let result = graph
+            .ro_query(query)
+            .execute()
+            .map_err(|e| anyhow::anyhow!("Query failed: {e}"))?;
+        for row in result.data {
+            for value in &row {
+                subgraph.write_value(out, keys, value)?;
+            }
+        }
+
+        let missing = subgraph.missing_endpoints();
+        let chunk_size = usize::try_from(page_size).unwrap_or(usize::MAX);
+        for chunk in missing.chunks(chunk_size) {
+            let ids: Vec<String> = chunk.iter().map(ToString::to_string).collect();
+            let query = format!("MATCH (e) WHERE ID(e) IN [{}] RETURN e", ids.join(", "));
+            let result = graph
+                .ro_query(&query)
+                .execute()
+                .map_err(|e| anyhow::anyhow!("Query failed: {e}"))?;
+            for row in result.data {
+                for value in &row {
+                    subgraph.write_value(out, keys, value)?;
+                }
+            }
+        }
+
+        Ok((subgraph.nodes.len() as u64, subgraph.edges.len() as u64))
+    }
+}
+
+/// A `<key>` declaration read from a GraphML file.
+#[derive(Debug, Default)]
+struct KeyDefinition {
+    domain: String,
+    name: String,
+    kind: String,
+    default: Option<String>,
+}
+
+/// A `<node>` or `<edge>` being read, with its `<data>` values by key ID.
+#[derive(Debug)]
+struct Element {
+    edge: bool,
+    id: String,
+    source: String,
+    target: String,
+    data: BTreeMap<String, String>,
+}
+
+/// Converts GraphML data text to a value of the key's `attr.type`.
+pub fn parse_graphml_value(text: &str, kind: &str) -> Result<serde_json::Value> {
+    Ok(match kind {
+        "boolean" => serde_json::json!(text.trim().eq_ignore_ascii_case("true")),
+        "int" | "long" => serde_json::json!(text
+            .trim()
+            .parse::<i64>()
+            .with_context(|| format!("'{text}' is not an integer"))?),
+        "float" | "double" => serde_json::json!(text
+            .trim()
+            .parse::<f64>()
+            .with_context(|| format!("'{text}' is not a number"))?),
+        _ => serde_json::json!(text),
+    })
+}
+
+fn attribute(element: &BytesStart<'_>, name: &str) -> Result<Option<String>> {
+    match element.try_get_attribute(name)? {
+        Some(attribute) => Ok(Some(
+            attribute
+                .normalized_value(XmlVersion::Implicit1_0)?
+                .into_owned(),
+        )),
+        None => Ok(None),
+    }
+}
+
+fn predefined_entity(name: &str) -> Option<char> {
+    match name {
+        "lt" => Some('<'),
+        "gt" => Some('>'),
+        "amp" => Some('&'),
+        "apos" => Some('\''),
+        "quot" => Some('"'),
+        _ => None,
+    }
+}
+
+/// Converts a parsed element into an import record using the key
+/// declarations: `labels` and `label` become node labels and the edge type,
+/// other keys typed properties.
+fn element_to_record(
+    element: Element,
+    keys: &HashMap<String, KeyDefinition>,
+) -> Result<JsonlRecord> {
+    let domain = if element.edge { "edge" } else { "node" };
+    let mut labels = Vec::new();
+    let mut rel_type = None;
+    let mut properties = serde_json::Map::new();
+
+    let applicable = keys
+        .iter()
+        .filter(|(_, key)| key.domain == domain || key.domain == "all");
+    for (id, key) in applicable {
+        let Some(text) = element.data.get(id).or(key.default.as_ref()) else {
+            continue;
+        };
+        match (element.edge, key.name.as_str()) {
+            (false, LABELS_KEY) => {
+                labels = text
+                    .split(':')
+                    .filter(|label| !label.is_empty())
+                    .map(ToString::to_string)
+                    .collect();
+            }
+            (true, TYPE_KEY) => rel_type = Some(text.clone()),
+            (_, name) => {
+                let value = parse_graphml_value(text, &key.kind)
+                    .with_context(|| format!("Key '{name}' of {domain} '{}'", element.id))?;
+                properties.insert(name.to_string(), value);
+            }
+        }
+    }
+
+    Ok(if element.edge {
+        JsonlRecord::Edge {
+            relationship: rel_type
+                .filter(|t| !t.is_empty())
+                .unwrap_or_else(|| DEFAULT_TYPE.to_string()),
+            src: serde_json::json!(element.source),
+            dst: serde_json::json!(element.target),
+            properties,
+        }
+    } else {
+        JsonlRecord::Node {
+            id: serde_json::json!(element.id),
+            labels,
+            properties,
+        }
+    })
+}
+
+/// Reads a GraphML document, passing each node and edge to `visit` as it is
+/// closed.
+pub fn read_graphml<R: io::BufRead>(
+    reader: R,
+    mut visit: impl FnMut(JsonlRecord, u64) -> Result<()>,
+) -> Result<()> {
+    let mut reader = Reader::from_reader(reader);
+    let mut keys: HashMap<String, KeyDefinition> = HashMap::new();
+    let mut key: Option<(String, KeyDefinition)> = None;
+    let mut element: Option<Element> = None;
+    // Key ID of the open <data>, or None for an open <default>
+    let mut text_target: Option<Option<String>> = None;
+    let mut text = String::new();
+    let mut buf = Vec::new();
+
+    loop {
+        let event = reader
+            .read_event_into(&mut buf)
+            .with_context(|| format!("Invalid GraphML at byte {}", reader.buffer_position()))?;
+        match event {
+            Event::Start(ref e) | Event::Empty(ref e) => {
+                let empty = matches!(event, Event::Empty(_));
+                match e.local_name().as_ref() {
+                    "key" => {
+                        let definition = KeyDefinition {
+                            domain: attribute(e, "for")?.unwrap_or_else(|| "all".to_string()),
+                            name: attribute(e, "attr.name")?.unwrap_or_default(),
+                            kind: attribute(e, "attr.type")?
+                                .unwrap_or_else(|| "string".to_string()),
+                            default: None,
+                        };
+                        let id = attribute(e, "id")?
+                            .ok_or_else(|| anyhow::anyhow!("<key> without an id"))?;
+                        if empty {
+                            keys.insert(id, definition);
+                        } else {
+                            key = Some((id, definition));
+                        }
+                    }
+                    "default" if key.is_some() && !empty => {
+                        text_target = Some(None);
+                        text.clear();
+                    }
+                    tag @ ("node" | "edge") => {
+                        let edge = tag == "edge";
+                        let id = attribute(e, "id")?.unwrap_or_default();
+                        let current = Element {
+                            edge,
+                            source: attribute(e, "source")?.unwrap_or_default(),
+                            target: attribute(e, "target")?.unwrap_or_default(),
+                            id,
+                            data: BTreeMap::new(),
+                        };
+                        if !edge && current.id.is_empty() {
+                            return Err(anyhow::anyhow!("<node> without an id"));
+                        }
+                        if empty {
+                            visit(element_to_record(current, &keys)?, reader.buffer_position())?;
+                        } else {
+                            element = Some(current);
+                        }
+                    }
+                    "data" if element.is_some() => {
+                        let id = attribute(e, "key")?
+                            .ok_or_else(|| anyhow::anyhow!("<data> without a key"))?;
+                        if !empty {
+                            text_target = Some(Some(id));
+                            text.clear();
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            Event::Text(ref e) if text_target.is_some() => text.push_str(&e.xml10_content()),
+            Event::CData(ref e) if text_target.is_some() => {
+                text.push_str(&e.xml10_content());
+            }
+            Event::GeneralRef(ref e) if text_target.is_some() => {
+                let resolved = match e.resolve_char_ref()? {
+                    Some(c) => Some(c),
+                    None => predefined_entity(e),
+                };
+                text.push(resolved.ok_or_else(|| anyhow::anyhow!("Unknown entity '&{};'", &**e))?);
+            }
+            Event::End(ref e) => match e.local_name().as_ref() {
+                "data" | "default" => match text_target.take() {
+                    Some(Some(id)) => {
+                        if let Some(element) = element.as_mut() {
+                            element.data.insert(id, std::mem::take(&mut text));
+                        }
+                    }
+                    Some(None) => {
+                        if let Some((_, definition)) = key.as_mut() {
+                            definition.default = Some(std::mem::take(&mut text));
+                        }
+                    }
+                    None => {}
+                },
+                "key" => {
+                    if let Some((id, definition)) = key.take() {
+                        keys.insert(id, definition);
+                    }
+                }
+                "node" | "edge" => {
+                    if let Some(current) = element.take() {
+                        visit(element_to_record(current, &keys)?, reader.buffer_position())?;
+                    }
+                }
+                _ => {}
+            },
+            Event::Eof => return Ok(()),
+            _ => {}
+        }
+        buf.clear();
+    }
+}
+
+/// Settings for `import graphml`.
+pub struct GraphmlImportOptions {
+    pub file: String,
+    pub graph: String,
+    pub batch_size: usize,
+}
+
+impl FalkorCli {
+    pub fn import_graphml(&self, options: &GraphmlImportOptions) -> Result<()> {
+        let file = File::open(&options.file)
+            .with_context(|| format!("Failed to open '{}'", options.file))?;
+        let size = file.metadata().map(|m| m.len()).ok();
+        let mut graph = self.client.select_graph(&options.graph);
+        let mut importer = RecordImporter::new(&mut graph, options.batch_size)?;
+
+        let mut progress = Progress::new(&format!("Importing {}", options.file), size);
+        read_graphml(BufReader::new(file), |record, position| {
+            importer.push(record)?;
+            progress.set(position);
+            Ok(())
+        })
+        .with_context(|| format!("Failed to import '{}'", options.file))?;
+        progress.finish();
+
+        importer.finish()
+    }
+}
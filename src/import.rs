@@ -0,0 +1,782 @@
+use anyhow::{Context, Result};
+use colored::Colorize;
+use falkordb::{FalkorValue, SyncGraph};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+use crate::client::FalkorCli;
+use crate::dump::{cleanup_statements, import_index_statement, IMPORT_ID, IMPORT_LABEL};
+use crate::params;
+use crate::progress::Progress;
+
+/// Property type declared in a CSV header, e.g. `age:INT`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PropertyType {
+    String,
+    Int,
+    Float,
+    Boolean,
+}
+
+/// Role of a CSV column, parsed from its header.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Column {
+    /// A property, optionally an array (`tags:STRING[]`, `;`-separated)
+    Property {
+        name: String,
+        kind: PropertyType,
+        array: bool,
+    },
+    /// The node ID, stored in the named property
+    Id {
+        property: String,
+    },
+    /// Relationship source node, optionally restricted to a label
+    StartId {
+        label: Option<String>,
+    },
+    /// Relationship target node, optionally restricted to a label
+    EndId {
+        label: Option<String>,
+    },
+    Ignore,
+}
+
+impl Column {
+    /// Parses a header cell of the form `name[:TYPE]`. An `:ID` column without
+    /// a name is stored in the `id` property.
+    pub fn parse(header: &str) -> Result<Self> {
+        let (name, raw_kind) = header.rsplit_once(':').unwrap_or((header, "STRING"));
+        let name = name.trim();
+        let raw_kind = raw_kind.trim();
+        let kind = raw_kind.to_uppercase();
+
+        let (kind, array) = kind
+            .strip_suffix("[]")
+            .map_or((kind.as_str(), false), |k| (k, true));
+
+        let id_label = |prefix: &str| -> Option<Option<String>> {
+            let rest = kind.strip_prefix(prefix)?;
+            if rest.is_empty() {
+                Some(None)
+            } else {
+                rest.strip_prefix('(')?.strip_suffix(')')?;
+                // Take the label from the original casing of the type
+                let open = raw_kind.find('(')?;
+                let close = raw_kind.rfind(')')?;
+                Some(Some(raw_kind[open + 1..close].to_string()))
+            }
+        };
+
+        if let Some(label) = id_label("START_ID") {
+            return Ok(Self::StartId { label });
+        }
+        if let Some(label) = id_label("END_ID") {
+            return Ok(Self::EndId { label });
+        }
+
+        let kind = match kind {
+            "ID" => {
+                let property = if name.is_empty() { "id" } else { name };
+                return Ok(Self::Id {
+                    property: property.to_string(),
+                });
+            }
+            "IGNORE" => return Ok(Self::Ignore),
+            "STRING" => PropertyType::String,
+            "INT" | "INTEGER" | "LONG" => PropertyType::Int,
+            "FLOAT" | "DOUBLE" => PropertyType::Float,
+            "BOOLEAN" | "BOOL" => PropertyType::Boolean,
+            other => {
+                return Err(anyhow::anyhow!(
+                    "Unknown type '{other}' in header '{header}'"
+                ))
+            }
+        };
+
+        if name.is_empty() {
+            return Err(anyhow::anyhow!("Column '{header}' has no property name"));
+        }
+        Ok(Self::Property {
+            name: name.to_string(),
+            kind,
+            array,
+        })
+    }
+}
+
+/// Converts a CSV cell to a typed value. Empty cells become `None` so the
+/// property is left unset.
+pub fn parse_cell(
+    cell: &str,
+    kind: PropertyType,
+    array: bool,
+) -> Result<Option<serde_json::Value>> {
+    if cell.is_empty() {
+        return Ok(None);
+    }
+    if array {
+        let items = cell
+            .split(';')
+            .map(|item| parse_scalar(item, kind))
+            .collect::<Result<Vec<_>>>()?;
+        return Ok(Some(serde_json::Value::Array(items)));
+    }
+    parse_scalar(cell, kind).map(Some)
+}
+
+fn parse_scalar(cell: &str, kind: PropertyType) -> Result<serde_json::Value> {
+    Ok(match kind {
+        PropertyType::String => serde_json::json!(cell),
+        PropertyType::Int => serde_json::json!(cell
+            .trim()
+            .parse::<i64>()
+            .with_context(|| format!("'{cell}' is not an integer"))?),
+        PropertyType::Float => serde_json::json!(cell
+            .trim()
+            .parse::<f64>()
+            .with_context(|| format!("'{cell}' is not a number"))?),
+        PropertyType::Boolean => match cell.trim().to_lowercase().as_str() {
+            "true" | "1" | "yes" => serde_json::json!(true),
+            "false" | "0" | "no" => serde_json::json!(false),
+            _ => return Err(anyhow::anyhow!("'{cell}' is not a boolean")),
+        },
+    })
+}
+
+/// Node and relationship IDs are integers when they look like one, so node
+/// files and relationship files agree on the type without declaring it.
+pub fn parse_id(cell: &str) -> serde_json::Value {
+    cell.parse::<i64>()
+        .map_or_else(|_| serde_json::json!(cell), |id| serde_json::json!(id))
+}
+
+/// Splits a `LABEL=FILE` argument.
+pub fn parse_source(arg: &str) -> Result<(&str, &str)> {
+    arg.split_once('=')
+        .filter(|(name, path)| !name.is_empty() && !path.is_empty())
+        .ok_or_else(|| anyhow::anyhow!("Expected LABEL=FILE, got '{arg}'"))
+}
+
+/// Quotes a label, relationship type or property name for use in Cypher.
+pub fn escape_name(name: &str) -> String {
+    format!("`{}`", name.replace('`', "``"))
+}
+
+/// Counters accumulated over the batches of an import.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ImportCounts {
+    pub rows: u64,
+    pub nodes_created: u64,
+    pub relationships_created: u64,
+}
+
+/// Notes on relationship rows that did not create exactly one relationship:
+/// rows whose endpoints were not found, and extra relationships created
+/// where an endpoint matched several nodes. Only the net difference is
+/// known, so at most one of the two is reported.
+pub fn relationship_notes(rows: u64, created: u64) -> Option<String> {
+    let skipped = rows.saturating_sub(created);
+    let extra = created.saturating_sub(rows);
+    if skipped > 0 {
+        Some(format!(
+            "{} skipped, endpoints not found",
+            skipped.to_string().yellow()
+        ))
+    } else if extra > 0 {
+        Some(format!(
+            "{} extra, endpoints matched several nodes",
+            extra.to_string().yellow()
+        ))
+    } else {
+        None
+    }
+}
+
+/// Runs `query` once with `$rows` bound to a batch of row maps, returning the
+/// number of nodes and relationships it created.
+pub fn run_batch(
+    graph: &mut SyncGraph,
+    query: &str,
+    rows: &[serde_json::Value],
+) -> Result<(u64, u64)> {
+    let params = std::collections::HashMap::from([(
+        "rows".to_string(),
+        params::json_to_cypher(&serde_json::Value::Array(rows.to_vec())),
+    )]);
+    let result = graph
+        .query(query)
+        .with_params(&params)
+        .execute()
+        .map_err(|e| anyhow::anyhow!("Batch failed: {e}"))?;
+    let created = |count: Option<i64>| count.and_then(|c| u64::try_from(c).ok()).unwrap_or(0);
+    Ok((
+        created(result.get_nodes_created()),
+        created(result.get_relationship_created()),
+    ))
+}
+
+/// Settings for `import csv`.
+pub struct CsvImportOptions {
+    pub graph: String,
+    pub nodes: Vec<String>,
+    pub relationships: Vec<String>,
+    pub batch_size: usize,
+    pub delimiter: u8,
+}
+
+impl FalkorCli {
+    pub fn import_csv(&self, options: &CsvImportOptions) -> Result<()> {
+        let mut graph = self.client.select_graph(&options.graph);
+        let mut totals = ImportCounts::default();
+        let mut id_properties: BTreeMap<String, String> = BTreeMap::new();
+
+        for source in &options.nodes {
+            let (labels, path) = parse_source(source)?;
+            let counts = import_csv_file(
+                &mut graph,
+                options,
+                labels,
+                Path::new(path),
+                true,
+                &mut id_properties,
+            )
+            .with_context(|| format!("Failed to import nodes from '{path}'"))?;
+            println!("  {labels}: {} nodes", counts.nodes_created);
+            totals.rows += counts.rows;
+            totals.nodes_created += counts.nodes_created;
+        }
+
+        // Index the ID properties so relationship endpoints can be found quickly
+        if !options.relationships.is_empty() {
+            for (label, property) in &id_properties {
+                let index = format!(
+                    "CREATE INDEX FOR (n:{}) ON (n.{})",
+                    escape_name(label),
+                    escape_name(property)
+                );
+                // The index may already exist
+                let _ = graph.query(&index).execute();
+            }
+        }
+
+        for source in &options.relationships {
+            let (rel_type, path) = parse_source(source)?;
+            let counts = import_csv_file(
+                &mut graph,
+                options,
+                rel_type,
+                Path::new(path),
+                false,
+                &mut id_properties,
+            )
+            .with_context(|| format!("Failed to import relationships from '{path}'"))?;
+            if let Some(note) = relationship_notes(counts.rows, counts.relationships_created) {
+                println!(
+                    "  {rel_type}: {} relationships ({note})",
+                    counts.relationships_created
+                );
+            } else {
+                println!(
+                    "  {rel_type}: {} relationships",
+                    counts.relationships_created
+                );
+            }
+            totals.rows += counts.rows;
+            totals.relationships_created += counts.relationships_created;
+        }
+
+        println!(
+            "{} {} nodes and {} relationships created from {} rows",
+            "Import complete:".green().bold(),
+            totals.nodes_created,
+            totals.relationships_created,
+            totals.rows
+        );
+        Ok(())
+    }
+}
+
+/// Imports one node or relationship file in batches.
+fn import_csv_file(
+    graph: &mut SyncGraph,
+    options: &CsvImportOptions,
+    name: &str,
+    path: &Path,
+    nodes: bool,
+    id_properties: &mut BTreeMap<String, String>,
+) -> Result<ImportCounts> {
+    let file = File::open(path)?;
+    let size = file.metadata().map(|m| m.len()).ok();
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(options.delimiter)
+        .from_reader(file);
+
+    let columns = reader
+        .headers()?
+        .iter()
+        .map(Column::parse)
+        .collect::<Result<Vec<_>>>()?;
+
+    let query = if nodes {
+        let id_property = columns.iter().find_map(|c| match c {
+            Column::Id { property } => Some(property.clone()),
+            _ => None,
+        });
+        if let Some(property) = id_property {
+            for label in name.split(':') {
+                id_properties.insert(label.to_string(), property.clone());
+            }
+        }
+        let labels: Vec<String> = name.split(':').map(escape_name).collect();
+        format!(
+            "UNWIND $rows AS row CREATE (n:{}) SET n = row",
+            labels.join(":")
+        )
+    } else {
+        relationship_query(name, &columns, id_properties)?
+    };
+
+    let mut progress = Progress::new(&format!("Importing {name}"), size);
+    let mut counts = ImportCounts::default();
+    let mut batch = Vec::with_capacity(options.batch_size);
+
+    for record in reader.records() {
+        let record = record?;
+        let line = record.position().map_or(0, csv::Position::line);
+        let row = if nodes {
+            node_row(&columns, &record)
+        } else {
+            relationship_row(&columns, &record)
+        }
+        .with_context(|| format!("{}:{line}", path.display()))?;
+        batch.push(row);
+        counts.rows += 1;
+
+        if batch.len() >= options.batch_size {
+            let (n, r) = run_batch(graph, &query, &batch)?;
+            counts.nodes_created += n;
+            counts.relationships_created += r;
+            batch.clear();
+            if let Some(position) = record.position() {
+                progress.set(position.byte());
+            }
+        }
+    }
+    if !batch.is_empty() {
+        let (n, r) = run_batch(graph, &query, &batch)?;
+        counts.nodes_created += n;
+        counts.relationships_created += r;
+    }
+    progress.finish();
+
+    Ok(counts)
+}
+
+fn relationship_query(
+    rel_type: &str,
+    columns: &[Column],
+    id_properties: &BTreeMap<String, String>,
+) -> Result<String> {
+    // Unlabeled endpoints use the ID property when every node file agrees on it
+    let shared: Vec<&String> = id_properties
+        .values()
+        .collect::<std::collections::BTreeSet<_>>()
+        .into_iter()
+        .collect();
+    let default_property = match shared.as_slice() {
+        [property] => property.as_str(),
+        _ => "id",
+    };
+
+    let endpoint = |label: &Option<String>, var: &str, key: &str| -> String {
+        match label {
+            Some(label) => {
+                let property = id_properties
+                    .get(label)
+                    .map_or(default_property, String::as_str);
+                format!(
+                    "({var}:{} {{{}: row.{key}}})",
+                    escape_name(label),
+                    escape_name(property)
+                )
+            }
+            None => format!("({var} {{{}: row.{key}}})", escape_name(default_property)),
+        }
+    };
+
+    let start = columns
+        .iter()
+        .find_map(|c| match c {
+            Column::StartId { label } => Some(label),
+            _ => None,
+        })
+        .ok_or_else(|| anyhow::anyhow!("Relationship files need a :START_ID column"))?;
+    let end = columns
+        .iter()
+        .find_map(|c| match c {
+            Column::EndId { label } => Some(label),
+            _ => None,
+        })
+        .ok_or_else(|| anyhow::anyhow!("Relationship files need an :END_ID column"))?;
+
+    Ok(format!(
+        "UNWIND $rows AS row MATCH {}, {} CREATE (a)-[r:{}]->(b) SET r = row.props",
+        endpoint(start, "a", "start"),
+        endpoint(end, "b", "end"),
+        escape_name(rel_type)
+    ))
+}
+
+fn properties(
+    columns: &[Column],
+    record: &csv::StringRecord,
+) -> Result<serde_json::Map<String, serde_json::Value>> {
+    let mut props = serde_json::Map::new();
+    for (column, cell) in columns.iter().zip(record.iter()) {
+        match column {
+            Column::Property { name, kind, array } => {
+                if let Some(value) =
+                    parse_cell(cell, *kind, *array).with_context(|| format!("Column '{name}'"))?
+                {
+                    props.insert(name.clone(), value);
+                }
+            }
+            Column::Id { property } if !cell.is_empty() => {
+                props.insert(property.clone(), parse_id(cell));
+            }
+            _ => {}
+        }
+    }
+    Ok(props)
+}
+
+fn node_row(columns: &[Column], record: &csv::StringRecord) -> Result<serde_json::Value> {
+    properties(columns, record).map(serde_json::Value::Object)
+}
+
+fn relationship_row(columns: &[Column], record: &csv::StringRecord) -> Result<serde_json::Value> {
+    let mut start = None;
+    let mut end = None;
+    for (column, cell) in columns.iter().zip(record.iter()) {
+        match column {
+            Column::StartId { .. } => start = Some(parse_id(cell)),
+            Column::EndId { .. } => end = Some(parse_id(cell)),
+            _ => {}
+        }
+    }
+
+    Ok(serde_json::json!({
+        "start": start,
+        "end": end,
+        "props": properties(columns, record)?,
+    }))
+}
+
+/// One line of a JSON Lines file, in the node and edge representation of
+/// `--format json` with an added `type` tag.
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum JsonlRecord {
+    Node {
+        id: serde_json::Value,
+        #[serde(default)]
+        labels: Vec<String>,
+        #[serde(default)]
+        properties: serde_json::Map<String, serde_json::Value>,
+    },
+    Edge {
+        relationship: String,
+        src: serde_json::Value,
+        dst: serde_json::Value,
+        #[serde(default)]
+        properties: serde_json::Map<String, serde_json::Value>,
+    },
+}
+
+/// Record properties that are not plain values: points, written as
+/// `{"latitude", "longitude"}` maps, and vectors, written as
+/// `{"vecf32": [...]}`. Neither map is a valid property value, so they are
+/// set separately with `point()` and `vecf32()`.
+#[derive(Debug, Default, PartialEq)]
+pub struct TypedProperties {
+    pub plain: serde_json::Map<String, serde_json::Value>,
+    pub points: serde_json::Map<String, serde_json::Value>,
+    pub vectors: serde_json::Map<String, serde_json::Value>,
+}
+
+impl TypedProperties {
+    pub fn split(properties: serde_json::Map<String, serde_json::Value>) -> Self {
+        let mut typed = Self::default();
+        for (key, value) in properties {
+            let map = value.as_object();
+            let is_point = map.is_some_and(|map| {
+                map.len() == 2
+                    && map
+                        .get("latitude")
+                        .is_some_and(serde_json::Value::is_number)
+                    && map
+                        .get("longitude")
+                        .is_some_and(serde_json::Value::is_number)
+            });
+            let vector = map
+                .filter(|map| map.len() == 1)
+                .and_then(|map| map.get("vecf32"))
+                .filter(|values| values.is_array())
+                .cloned();
+            if is_point {
+                typed.points.insert(key, value);
+            } else if let Some(values) = vector {
+                typed.vectors.insert(key, values);
+            } else {
+                typed.plain.insert(key, value);
+            }
+        }
+        typed
+    }
+
+    /// The record row sent to the server, with `extra` fields such as the ID.
+    fn into_row(self, extra: serde_json::Value) -> serde_json::Value {
+        let mut row = extra;
+        row["properties"] = serde_json::Value::Object(self.plain);
+        row["points"] = serde_json::Value::Object(self.points);
+        row["vectors"] = serde_json::Value::Object(self.vectors);
+        row
+    }
+}
+
+/// `SET` items that restore the points and vectors of a batch of rows on
+/// `var`, e.g. `` , n.`loc` = CASE WHEN row.points.`loc` IS NULL THEN NULL
+/// ELSE point(row.points.`loc`) END``.
+pub fn typed_assignments(var: &str, rows: &[serde_json::Value]) -> String {
+    let mut assignments = String::new();
+    for (field, function) in [("points", "point"), ("vectors", "vecf32")] {
+        let keys: std::collections::BTreeSet<&String> = rows
+            .iter()
+            .filter_map(|row| row[field].as_object())
+            .flat_map(serde_json::Map::keys)
+            .collect();
+        for key in keys {
+            let value = format!("row.{field}.{}", escape_name(key));
+            assignments.push_str(&format!(
+                ", {var}.{} = CASE WHEN {value} IS NULL THEN NULL ELSE {function}({value}) END",
+                escape_name(key)
+            ));
+        }
+    }
+    assignments
+}
+
+/// Settings for `import jsonl`.
+pub struct JsonlImportOptions {
+    pub graph: String,
+    pub file: String,
+    pub batch_size: usize,
+}
+
+/// Creates nodes and edges from a stream of [`JsonlRecord`]s, batching rows
+/// through `UNWIND`. Rows are grouped by label set or relationship type since
+/// those cannot be parameters. Edges find their endpoints by the record `id`,
+/// held in a temporary property, so nodes must come before the edges that
+/// reference them.
+pub struct RecordImporter<'a> {
+    graph: &'a mut SyncGraph,
+    batch_size: usize,
+    nodes: BTreeMap<Vec<String>, Vec<serde_json::Value>>,
+    edges: BTreeMap<String, Vec<serde_json::Value>>,
+    counts: ImportCounts,
+    edge_rows: u64,
+    seen_edges: bool,
+}
+
+impl<'a> RecordImporter<'a> {
+    pub fn new(graph: &'a mut SyncGraph, batch_size: usize) -> Result<Self> {
+        // Nodes left by an import that did not finish still carry their
+        // record IDs, so new edges would attach to them
+        let leftover = graph
+            .ro_query(&format!("MATCH (n:{IMPORT_LABEL}) RETURN count(n)"))
+            .execute()
+            .map_err(|e| anyhow::anyhow!("Query failed: {e}"))?
+            .data
+            .next()
+            .and_then(|row| row.into_iter().next());
+        if let Some(FalkorValue::I64(count @ 1..)) = leftover {
+            return Err(anyhow::anyhow!(
+                "The graph holds {count} nodes left by an import that did not finish. \
+                 Delete them, or keep them with `{}`, then import again",
+                cleanup_statements()[1]
+            ));
+        }
+
+        // The index alone may remain when no node was created
+        let indexed = graph
+            .list_indices()
+            .map_err(|e| anyhow::anyhow!("Failed to list indices: {e}"))?
+            .data
+            .iter()
+            .any(|index| {
+                index.index_label == IMPORT_LABEL && index.fields.iter().any(|f| f == IMPORT_ID)
+            });
+        if !indexed {
+            graph
+                .query(&import_index_statement())
+                .execute()
+                .map_err(|e| anyhow::anyhow!("Query failed: {e}"))?;
+        }
+        Ok(Self {
+            graph,
+            batch_size,
+            nodes: BTreeMap::new(),
+            edges: BTreeMap::new(),
+            counts: ImportCounts::default(),
+            edge_rows: 0,
+            seen_edges: false,
+        })
+    }
+
+    pub fn push(&mut self, record: JsonlRecord) -> Result<()> {
+        self.counts.rows += 1;
+        match record {
+            JsonlRecord::Node {
+                id,
+                mut labels,
+                properties,
+            } => {
+                labels.sort();
+                let rows = self.nodes.entry(labels.clone()).or_default();
+                let row =
+                    TypedProperties::split(properties).into_row(serde_json::json!({"id": id}));
+                rows.push(row);
+                if rows.len() >= self.batch_size {
+                    self.flush_nodes(&labels)?;
+                }
+            }
+            JsonlRecord::Edge {
+                relationship,
+                src,
+                dst,
+                properties,
+            } => {
+                if !self.seen_edges {
+                    // Endpoints must exist before the first edge is created
+                    self.flush_all_nodes()?;
+                    self.seen_edges = true;
+                }
+                self.edge_rows += 1;
+                let rows = self.edges.entry(relationship.clone()).or_default();
+                let row = TypedProperties::split(properties)
+                    .into_row(serde_json::json!({"src": src, "dst": dst}));
+                rows.push(row);
+                if rows.len() >= self.batch_size {
+                    self.flush_edges(&relationship)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Sends the remaining batches, removes the temporary import IDs and
+    /// prints the final counts.
+    pub fn finish(mut self) -> Result<()> {
+        self.flush_all_nodes()?;
+        let types: Vec<String> = self.edges.keys().cloned().collect();
+        for rel_type in &types {
+            self.flush_edges(rel_type)?;
+        }
+        for statement in cleanup_statements() {
+            self.graph
+                .query(&statement)
+                .execute()
+                .map_err(|e| anyhow::anyhow!("Query failed: {e}"))?;
+        }
+
+        let counts = self.counts;
+        if let Some(note) = relationship_notes(self.edge_rows, counts.relationships_created) {
+            println!("  edges: {note}");
+        }
+        println!(
+            "{} {} nodes and {} relationships created from {} records",
+            "Import complete:".green().bold(),
+            counts.nodes_created,
+            counts.relationships_created,
+            counts.rows
+        );
+        Ok(())
+    }
+
+    fn flush_nodes(&mut self, labels: &[String]) -> Result<()> {
+        let Some(rows) = self.nodes.get_mut(labels).filter(|rows| !rows.is_empty()) else {
+            return Ok(());
+        };
+        let labels: String = labels
+            .iter()
+            .map(|label| format!(":{}", escape_name(label)))
+            .collect();
+        let query = format!(
+            "UNWIND $rows AS row CREATE (n{labels}:{IMPORT_LABEL}) \
+             SET n = row.properties, n.{IMPORT_ID} = row.id{}",
+            typed_assignments("n", rows)
+        );
+        let (nodes, _) = run_batch(self.graph, &query, rows)?;
+        self.counts.nodes_created += nodes;
+        rows.clear();
+        Ok(())
+    }
+
+    fn flush_edges(&mut self, rel_type: &str) -> Result<()> {
+        let Some(rows) = self.edges.get_mut(rel_type).filter(|rows| !rows.is_empty()) else {
+            return Ok(());
+        };
+        let query = format!(
+            "UNWIND $rows AS row \
+             MATCH (a:{IMPORT_LABEL} {{{IMPORT_ID}: row.src}}), (b:{IMPORT_LABEL} {{{IMPORT_ID}: row.dst}}) \
+             CREATE (a)-[r:{}]->(b) SET r = row.properties{}",
+            escape_name(rel_type),
+            typed_assignments("r", rows)
+        );
+        let (_, relationships) = run_batch(self.graph, &query, rows)?;
+        self.counts.relationships_created += relationships;
+        rows.clear();
+        Ok(())
+    }
+
+    fn flush_all_nodes(&mut self) -> Result<()> {
+        let groups: Vec<Vec<String>> = self.nodes.keys().cloned().collect();
+        groups
+            .iter()
+            .try_for_each(|labels| self.flush_nodes(labels))
+    }
+}
+
+impl FalkorCli {
+    /// Imports nodes and edges from a JSON Lines file, as written by
+    /// `export jsonl`.
+    pub fn import_jsonl(&self, options: &JsonlImportOptions) -> Result<()> {
+        let path = Path::new(&options.file);
+        let file =
+            File::open(path).with_context(|| format!("Failed to open '{}'", options.file))?;
+        let size = file.metadata().map(|m| m.len()).ok();
+        let mut graph = self.client.select_graph(&options.graph);
+        let mut importer = RecordImporter::new(&mut graph, options.batch_size)?;
+
+        let mut progress = Progress::new(&format!("Importing {}", options.file), size);
+        let mut position = 0;
+        for (i, line) in BufReader::new(file).lines().enumerate() {
+            let line = line?;
+            position += line.len() as u64 + 1;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let record: JsonlRecord = serde_json::from_str(&line)
+                .with_context(|| format!("{}:{}", options.file, i + 1))?;
+            importer.push(record)?;
+            progress.set(position);
+        }
+        progress.finish();
+
+        importer.finish()
+    }
+}
@@ -4,9 +4,14 @@ use rustyline::history::DefaultHistory;
 use rustyline::{error::ReadlineError, Editor};
 use std::env;
 use std::path::PathBuf;
+use std::sync::atomic::Ordering;
 
 use crate::client::FalkorCli;
 use crate::completion::SimpleCompleter;
+use crate::dump;
+use crate::editor;
+use crate::format;
+use crate::output::{self, OutputFile};
 
 impl FalkorCli {
     pub fn interactive_mode(&mut self) -> Result<()> {
@@ -48,7 +53,7 @@ impl FalkorCli {
                     let _ = rl.add_history_entry(line.as_str());
 
                     if let Err(e) = self.handle_interactive_command(&line) {
-                        eprintln!("{}: {}", "Error".red(), e);
+                        eprintln!("{}: {}", self.format_options.theme.error.paint("Error"), e);
                     }
 
                     if line.trim() == "exit" || line.trim() == "quit" {
@@ -78,7 +83,8 @@ impl FalkorCli {
         Ok(())
     }
 
-    fn handle_interactive_command(&mut self, line: &str) -> Result<()> {
+    #[allow(clippy::too_many_lines)]
+    pub fn handle_interactive_command(&mut self, line: &str) -> Result<()> {
         let line = line.trim();
 
         if line.is_empty() {
@@ -112,6 +118,105 @@ impl FalkorCli {
                 self.show_schema(&graph_name)
             }
             Some("EXIT" | "QUIT") => Ok(()),
+            Some("\\O") => {
+                let path = Self::command_argument(line);
+                if let Some(file) = self.output_file.take() {
+                    file.finish()?;
+                }
+                self.binary_written.store(false, Ordering::Relaxed);
+                if path.is_empty() {
+                    println!("Output restored to stdout");
+                } else {
+                    self.output_file = Some(OutputFile::create(path)?);
+                    println!("Writing query results to {}", path.yellow());
+                }
+                Ok(())
+            }
+            Some("\\LOG") => {
+                let path = Self::command_argument(line);
+                if path.is_empty() {
+                    self.query_log = None;
+                    println!("Query logging stopped");
+                } else {
+                    self.query_log = Some(output::open_log_file(path)?);
+                    println!("Logging statements and results to {}", path.yellow());
+                }
+                Ok(())
+            }
+            Some("\\FORMAT") => {
+                match parts.get(1).map(|f| f.to_lowercase()).as_deref() {
+                    Some(name) => {
+                        let info = format::lookup(name).ok_or_else(|| {
+                            anyhow::anyhow!(
+                                "Unknown format '{name}'. Use one of: {}",
+                                format::names().join(", ")
+                            )
+                        })?;
+                        self.format = info.name.to_string();
+                        println!("Output format is {}", info.name.yellow());
+                    }
+                    None => println!("Output format is {}", self.format.yellow()),
+                }
+                Ok(())
+            }
+            Some("\\E") => {
+                let buffer =
+                    editor::edit_statement(self.last_statement.as_deref().unwrap_or_default())?;
+                if buffer.trim().is_empty() {
+                    println!("Nothing to execute");
+                    return Ok(());
+                }
+                println!("{}", buffer.trim());
+                self.handle_interactive_command(&buffer)
+            }
+            Some("\\I" | "SOURCE") => {
+                let path = Self::command_argument(line);
+                if path.is_empty() {
+                    println!("Usage: \\i <file>");
+                    return Ok(());
+                }
+                self.run_script(path)
+            }
+            Some("\\ECHO") => {
+                match parts.get(1).map(|s| s.to_lowercase()).as_deref() {
+                    Some("on") => self.echo = true,
+                    Some("off") => self.echo = false,
+                    _ => {}
+                }
+                println!("Echo is {}", if self.echo { "on" } else { "off" });
+                Ok(())
+            }
+            Some("\\PARAM") => {
+                let mut args = Self::command_argument(line).splitn(2, char::is_whitespace);
+                match (args.next().filter(|n| !n.is_empty()), args.next()) {
+                    (Some(name), Some(value)) => {
+                        let value: serde_json::Value =
+                            serde_json::from_str(value.trim()).map_err(|e| {
+                                anyhow::anyhow!("Invalid JSON value for parameter '{name}': {e}")
+                            })?;
+                        let name = name.trim_start_matches('$').to_string();
+                        println!("{} = {}", format!("${name}").yellow(), value);
+                        self.params.insert(name, value);
+                    }
+                    _ => println!("Usage: \\param <name> <json value>"),
+                }
+                Ok(())
+            }
+            Some("\\PARAMS") => {
+                if self.params.is_empty() {
+                    println!("No session parameters set");
+                }
+                for (name, value) in &self.params {
+                    println!("{} = {}", format!("${name}").yellow(), value);
+                }
+                Ok(())
+            }
+            Some("\\PAGER") => {
+                self.pager = !self.pager;
+                println!("Pager is {}", if self.pager { "on" } else { "off" });
+                Ok(())
+            }
+            Some("\\CONSTRAINT") => self.create_constraint(Self::command_argument(line)),
             Some("QUERY") => {
                 // Extract the query text after the leading 'QUERY' token (split on any whitespace)
                 let raw = line
@@ -130,6 +235,7 @@ impl FalkorCli {
                     println!("Usage: QUERY <cypher_query>");
                     return Ok(());
                 }
+                self.last_statement = Some(line.to_string());
 
                 self.current_graph.clone().as_ref().map_or_else(
                     || {
@@ -158,6 +264,7 @@ impl FalkorCli {
                     println!("Usage: RO-QUERY <cypher_query>");
                     return Ok(());
                 }
+                self.last_statement = Some(line.to_string());
 
                 self.current_graph.clone().as_ref().map_or_else(
                     || {
@@ -170,6 +277,7 @@ impl FalkorCli {
             }
             _ => {
                 // Treat as Cypher query if we have a current graph
+                self.last_statement = Some(line.to_string());
                 self.current_graph.clone().as_ref().map_or_else(
                     || {
                         Err(anyhow::anyhow!(
@@ -182,6 +290,55 @@ impl FalkorCli {
         }
     }
 
+    /// Returns everything after the command token, used for file path arguments
+    /// that may contain spaces.
+    fn command_argument(line: &str) -> &str {
+        line.split_once(char::is_whitespace)
+            .map_or("", |(_, rest)| rest.trim())
+    }
+
+    /// Creates a constraint on the current graph from
+    /// `UNIQUE|MANDATORY NODE|RELATIONSHIP <label> <property>...`, where
+    /// names holding spaces are quoted with backticks.
+    fn create_constraint(&mut self, args: &str) -> Result<()> {
+        let args = dump::split_names(args)?;
+        let [kind, entity, label, properties @ ..] = args.as_slice() else {
+            return Err(anyhow::anyhow!(
+                "Usage: \\constraint UNIQUE|MANDATORY NODE|RELATIONSHIP <label> <property>..."
+            ));
+        };
+        if properties.is_empty() {
+            return Err(anyhow::anyhow!("Constraint needs at least one property"));
+        }
+        let properties: Vec<&str> = properties.iter().map(String::as_str).collect();
+        let entity = match entity.to_uppercase().as_str() {
+            "NODE" => falkordb::EntityType::Node,
+            "EDGE" | "RELATIONSHIP" => falkordb::EntityType::Edge,
+            _ => {
+                return Err(anyhow::anyhow!(
+                    "Invalid entity type. Use NODE or RELATIONSHIP"
+                ))
+            }
+        };
+        let graph_name = self.get_graph_name(None)?;
+        let mut graph = self.client.select_graph(&graph_name);
+        let result = match kind.to_uppercase().as_str() {
+            "UNIQUE" => graph.create_unique_constraint(entity, label.clone(), &properties),
+            "MANDATORY" => graph.create_mandatory_constraint(entity, label, &properties),
+            _ => {
+                return Err(anyhow::anyhow!(
+                    "Invalid constraint type. Use UNIQUE or MANDATORY"
+                ))
+            }
+        };
+        result.map_err(|e| anyhow::anyhow!("Constraint creation failed: {e}"))?;
+        println!(
+            "Constraint created on {label} for {}",
+            properties.join(", ")
+        );
+        Ok(())
+    }
+
     fn show_help() {
         println!("{}", "FalkorDB CLI Commands:".green().bold());
         println!(
@@ -201,6 +358,47 @@ impl FalkorCli {
             "  {}   - Show schema for specific graph",
             "SCHEMA <graph>".cyan()
         );
+        println!(
+            "  {}                - Edit the last statement in $EDITOR and run it",
+            "\\e".cyan()
+        );
+        println!(
+            "  {}          - Run the statements of a script file",
+            "\\i <file>".cyan()
+        );
+        println!(
+            "  {}     - Echo statements run from scripts",
+            "\\echo on|off".cyan()
+        );
+        println!(
+            "  {} - Set a session parameter sent with queries using $name",
+            "\\param <name> <json>".cyan()
+        );
+        println!(
+            "  {}           - List session parameters",
+            "\\params".cyan()
+        );
+        println!(
+            "  {}       - Set output format ({})",
+            "\\format <fmt>".cyan(),
+            format::names().join(", ")
+        );
+        println!(
+            "  {}          - Write query results to a file (no file: stdout)",
+            "\\o [file]".cyan()
+        );
+        println!(
+            "  {}        - Append statements and results to a log (no file: stop)",
+            "\\log [file]".cyan()
+        );
+        println!(
+            "  {}            - Toggle paging of long results",
+            "\\pager".cyan()
+        );
+        println!(
+            "  {} - Create a constraint (UNIQUE|MANDATORY NODE|RELATIONSHIP <label> <prop>...)",
+            "\\constraint ...".cyan()
+        );
         println!("  {}              - Show this help", "HELP".cyan());
         println!("  {}        - Exit interactive mode", "EXIT/QUIT".cyan());
         println!();
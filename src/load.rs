@@ -0,0 +1,540 @@
+use anyhow::{Context, Result};
+use colored::Colorize;
+use falkordb::FalkorSyncClient;
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::num::NonZeroU16;
+use std::path::Path;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::bench::{panic_message, LatencySummary};
+use crate::client::{FalkorCli, POOL_LIMIT};
+use crate::params;
+
+/// A workload file, e.g.:
+///
+/// ```yaml
+/// duration: 60
+/// rate: 500
+/// concurrency: 8
+/// queries:
+///   - name: lookup
+///     query: "MATCH (p:Person {id: $id}) RETURN p"
+///     weight: 8
+///     readonly: true
+///     params:
+///       id: { type: int, min: 1, max: 100000 }
+/// ```
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Workload {
+    /// Run time in seconds
+    pub duration: Option<f64>,
+    /// Target queries per second across all workers; unlimited when unset
+    pub rate: Option<f64>,
+    pub concurrency: Option<NonZeroU16>,
+    /// Seed for the parameter generators, for reproducible runs
+    pub seed: Option<u64>,
+    pub queries: Vec<WorkloadQuery>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WorkloadQuery {
+    pub name: String,
+    pub query: String,
+    #[serde(default = "default_weight")]
+    pub weight: u32,
+    #[serde(default)]
+    pub readonly: bool,
+    #[serde(default)]
+    pub params: BTreeMap<String, ParamGenerator>,
+}
+
+const fn default_weight() -> u32 {
+    1
+}
+
+/// How to produce a value for a query parameter on each run.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase", deny_unknown_fields)]
+pub enum ParamGenerator {
+    /// A uniformly random integer in `min..=max`
+    Int { min: i64, max: i64 },
+    /// One of the listed values, picked uniformly
+    Choice { values: Vec<serde_json::Value> },
+    /// An increasing counter shared by all workers
+    Sequence {
+        #[serde(default)]
+        start: i64,
+        #[serde(default = "default_step")]
+        step: i64,
+    },
+    /// A value sampled from a column of a CSV file with a header row
+    Csv { file: String, column: String },
+}
+
+const fn default_step() -> i64 {
+    1
+}
+
+impl Workload {
+    pub fn parse(yaml: &str) -> Result<Self> {
+        let workload: Self = serde_yaml::from_str(yaml)?;
+        if workload.queries.is_empty() {
+            return Err(anyhow::anyhow!("Workload defines no queries"));
+        }
+        if workload.queries.iter().all(|q| q.weight == 0) {
+            return Err(anyhow::anyhow!(
+                "At least one query needs a non-zero weight"
+            ));
+        }
+        for query in &workload.queries {
+            for (name, generator) in &query.params {
+                match generator {
+                    ParamGenerator::Int { min, max } if min > max => {
+                        return Err(anyhow::anyhow!(
+                            "Parameter '{name}' of '{}' has min greater than max",
+                            query.name
+                        ));
+                    }
+                    ParamGenerator::Choice { values } if values.is_empty() => {
+                        return Err(anyhow::anyhow!(
+                            "Parameter '{name}' of '{}' has no choices",
+                            query.name
+                        ));
+                    }
+                    _ => {}
+                }
+            }
+        }
+        Ok(workload)
+    }
+}
+
+/// Small SplitMix64 generator; statistical quality is plenty for picking
+/// queries and parameter values.
+pub struct Rng(u64);
+
+impl Rng {
+    pub const fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform value in `0..bound`; `bound` must be non-zero.
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn below(&mut self, bound: u64) -> u64 {
+        ((u128::from(self.next_u64()) * u128::from(bound)) >> 64) as u64
+    }
+
+    /// Uniform value in `min..=max`.
+    #[allow(clippy::cast_possible_wrap, clippy::cast_sign_loss)]
+    pub fn range(&mut self, min: i64, max: i64) -> i64 {
+        let span = max.wrapping_sub(min) as u64;
+        if span == u64::MAX {
+            return self.next_u64() as i64;
+        }
+        min.wrapping_add(self.below(span + 1) as i64)
+    }
+}
+
+/// Runtime state of a parameter generator, shared by all workers.
+enum Generator {
+    Int { min: i64, max: i64 },
+    Choice(Vec<serde_json::Value>),
+    Sequence { next: AtomicI64, step: i64 },
+    Samples(Vec<serde_json::Value>),
+}
+
+impl Generator {
+    fn prepare(spec: &ParamGenerator, base_dir: &Path) -> Result<Self> {
+        Ok(match spec {
+            ParamGenerator::Int { min, max } => Self::Int {
+                min: *min,
+                max: *max,
+            },
+            ParamGenerator::Choice { values } => Self::Choice(values.clone()),
+            ParamGenerator::Sequence { start, step } => Self::Sequence {
+                next: AtomicI64::new(*start),
+                step: *step,
+            },
+            ParamGenerator::Csv { file, column } => {
+                let path = base_dir.join(file);
+                let values = read_csv_column(&path, column)
+                    .with_context(|| format!("Failed to read '{}'", path.display()))?;
+                if values.is_empty() {
+                    return Err(anyhow::anyhow!("'{}' has no rows", path.display()));
+                }
+                Self::Samples(values)
+            }
+        })
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn generate(&self, rng: &mut Rng) -> serde_json::Value {
+        match self {
+            Self::Int { min, max } => serde_json::json!(rng.range(*min, *max)),
+            Self::Choice(values) | Self::Samples(values) => {
+                values[rng.below(values.len() as u64) as usize].clone()
+            }
+            Self::Sequence { next, step } => {
+                serde_json::json!(next.fetch_add(*step, Ordering::Relaxed))
+            }
+        }
+    }
+}
+
+/// Reads one column of a CSV file. Numeric-looking cells become numbers so
+/// they match integer properties in the graph.
+fn read_csv_column(path: &Path, column: &str) -> Result<Vec<serde_json::Value>> {
+    let mut reader = csv::Reader::from_path(path)?;
+    let index = reader
+        .headers()?
+        .iter()
+        .position(|h| h == column)
+        .ok_or_else(|| anyhow::anyhow!("No column named '{column}'"))?;
+
+    let mut values = Vec::new();
+    for record in reader.records() {
+        let record = record?;
+        let cell = record.get(index).unwrap_or_default();
+        values.push(
+            cell.parse::<i64>()
+                .map(serde_json::Value::from)
+                .or_else(|_| cell.parse::<f64>().map(serde_json::Value::from))
+                .unwrap_or_else(|_| serde_json::Value::from(cell)),
+        );
+    }
+    Ok(values)
+}
+
+struct PreparedQuery<'a> {
+    spec: &'a WorkloadQuery,
+    generators: Vec<(String, Generator)>,
+}
+
+/// Per-query outcome collected by one worker.
+#[derive(Default)]
+struct QueryStats {
+    latencies_ms: Vec<f64>,
+    errors: u64,
+    last_error: Option<String>,
+}
+
+impl QueryStats {
+    fn merge(&mut self, other: Self) {
+        self.latencies_ms.extend(other.latencies_ms);
+        self.errors += other.errors;
+        if other.last_error.is_some() {
+            self.last_error = other.last_error;
+        }
+    }
+}
+
+/// Settings for the `load` subcommand; CLI values override the workload file.
+pub struct LoadOptions {
+    pub graph: String,
+    pub workload: String,
+    pub duration: Option<f64>,
+    pub rate: Option<f64>,
+    pub concurrency: Option<NonZeroU16>,
+}
+
+impl FalkorCli {
+    pub fn run_load(&self, options: &LoadOptions) -> Result<()> {
+        let path = Path::new(&options.workload);
+        let yaml = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read workload '{}'", path.display()))?;
+        let workload = Workload::parse(&yaml)
+            .with_context(|| format!("Invalid workload '{}'", path.display()))?;
+
+        let duration = options.duration.or(workload.duration).unwrap_or(60.0);
+        if !duration.is_finite() || duration <= 0.0 {
+            return Err(anyhow::anyhow!("Duration must be a positive number"));
+        }
+        let duration = Duration::from_secs_f64(duration);
+        let rate = options.rate.or(workload.rate);
+        if rate.is_some_and(|r| !r.is_finite() || r <= 0.0) {
+            return Err(anyhow::anyhow!("Rate must be a positive number"));
+        }
+        let concurrency = options
+            .concurrency
+            .or(workload.concurrency)
+            .unwrap_or(NonZeroU16::MIN);
+
+        let base_dir = path.parent().unwrap_or_else(|| Path::new("."));
+        let queries = workload
+            .queries
+            .iter()
+            .map(|spec| {
+                let generators = spec
+                    .params
+                    .iter()
+                    .map(|(name, generator)| {
+                        Generator::prepare(generator, base_dir).map(|g| (name.clone(), g))
+                    })
+                    .collect::<Result<_>>()?;
+                Ok(PreparedQuery { spec, generators })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let seed = workload.seed.unwrap_or_else(|| {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_secs())
+        });
+        // Each worker gets an equal share of the target rate
+        let worker_interval =
+            rate.map(|r| Duration::from_secs_f64(f64::from(concurrency.get()) / r));
+
+        let clients =
+            Self::connect_workers(&self.connection_string, usize::from(concurrency.get()))?;
+        let started = Instant::now();
+
+        let per_worker = thread::scope(|scope| {
+            let workers: Vec<_> = (0..concurrency.get())
+                .map(|worker| {
+                    let mut rng = Rng::new(seed ^ u64::from(worker).wrapping_mul(0x2545_f491));
+                    let client = &clients[usize::from(worker) / POOL_LIMIT];
+                    let queries = &queries;
+                    let graph = options.graph.as_str();
+                    let session = &self.params;
+                    scope.spawn(move || {
+                        let worker = LoadWorker {
+                            client,
+                            graph,
+                            queries,
+                            session,
+                            started,
+                            duration,
+                            interval: worker_interval,
+                        };
+                        worker.run(&mut rng)
+                    })
+                })
+                .collect();
+            workers
+                .into_iter()
+                .map(|worker| {
+                    worker.join().map_err(|payload| {
+                        anyhow::anyhow!("A load worker panicked: {}", panic_message(&*payload))
+                    })
+                })
+                .collect::<Result<Vec<Vec<QueryStats>>>>()
+        })?;
+        let elapsed = started.elapsed();
+
+        let mut totals: Vec<QueryStats> = queries.iter().map(|_| QueryStats::default()).collect();
+        for worker_stats in per_worker {
+            for (total, stats) in totals.iter_mut().zip(worker_stats) {
+                total.merge(stats);
+            }
+        }
+
+        let report = LoadReport {
+            names: queries.iter().map(|q| q.spec.name.as_str()).collect(),
+            stats: totals,
+            elapsed,
+        };
+        if self.format == "json" {
+            println!("{}", serde_json::to_string_pretty(&report.to_json())?);
+        } else {
+            report.print_table();
+        }
+        Ok(())
+    }
+}
+
+struct LoadWorker<'a> {
+    client: &'a FalkorSyncClient,
+    graph: &'a str,
+    queries: &'a [PreparedQuery<'a>],
+    session: &'a BTreeMap<String, serde_json::Value>,
+    started: Instant,
+    duration: Duration,
+    interval: Option<Duration>,
+}
+
+impl LoadWorker<'_> {
+    fn run(&self, rng: &mut Rng) -> Vec<QueryStats> {
+        let mut graph = self.client.select_graph(self.graph);
+        let mut stats: Vec<QueryStats> =
+            self.queries.iter().map(|_| QueryStats::default()).collect();
+        let total_weight: u64 = self.queries.iter().map(|q| u64::from(q.spec.weight)).sum();
+        let mut next_at = Instant::now();
+
+        while self.started.elapsed() < self.duration {
+            if let Some(interval) = self.interval {
+                let now = Instant::now();
+                if next_at > now {
+                    thread::sleep(next_at - now);
+                }
+                next_at += interval;
+            }
+
+            let index = self.pick(rng, total_weight);
+            let query = &self.queries[index];
+            let values: BTreeMap<String, serde_json::Value> = query
+                .generators
+                .iter()
+                .map(|(name, generator)| (name.clone(), generator.generate(rng)))
+                .collect();
+
+            let request_started = Instant::now();
+            let result = run_one(&mut graph, query.spec, &values, self.session);
+            let entry = &mut stats[index];
+            match result {
+                Ok(()) => entry
+                    .latencies_ms
+                    .push(request_started.elapsed().as_secs_f64() * 1000.0),
+                Err(e) => {
+                    entry.errors += 1;
+                    entry.last_error = Some(e.to_string());
+                }
+            }
+        }
+
+        stats
+    }
+
+    /// Picks a query index with probability proportional to its weight.
+    #[allow(clippy::cast_possible_truncation)]
+    fn pick(&self, rng: &mut Rng, total_weight: u64) -> usize {
+        let mut target = rng.below(total_weight);
+        for (i, query) in self.queries.iter().enumerate() {
+            let weight = u64::from(query.spec.weight);
+            if target < weight {
+                return i;
+            }
+            target -= weight;
+        }
+        self.queries.len() - 1
+    }
+}
+
+fn run_one(
+    graph: &mut falkordb::SyncGraph,
+    spec: &WorkloadQuery,
+    values: &BTreeMap<String, serde_json::Value>,
+    session: &BTreeMap<String, serde_json::Value>,
+) -> Result<()> {
+    let bound: HashMap<String, String> = params::bind_parameters(&spec.query, values, session)?;
+    let result = if spec.readonly {
+        graph.ro_query(&spec.query).with_params(&bound).execute()
+    } else {
+        graph.query(&spec.query).with_params(&bound).execute()
+    }?;
+    result.data.for_each(drop);
+    Ok(())
+}
+
+struct LoadReport<'a> {
+    names: Vec<&'a str>,
+    stats: Vec<QueryStats>,
+    elapsed: Duration,
+}
+
+impl LoadReport<'_> {
+    #[allow(clippy::cast_precision_loss)]
+    fn throughput(&self, count: usize) -> f64 {
+        let secs = self.elapsed.as_secs_f64();
+        if secs > 0.0 {
+            count as f64 / secs
+        } else {
+            0.0
+        }
+    }
+
+    fn to_json(&self) -> serde_json::Value {
+        let queries: Vec<serde_json::Value> = self
+            .names
+            .iter()
+            .zip(&self.stats)
+            .map(|(name, stats)| {
+                let mut latencies = stats.latencies_ms.clone();
+                let latency = LatencySummary::from_samples(&mut latencies);
+                serde_json::json!({
+                    "name": name,
+                    "requests": stats.latencies_ms.len(),
+                    "errors": stats.errors,
+                    "throughput": self.throughput(stats.latencies_ms.len()),
+                    "latency_ms": latency.map(LatencySummary::to_json),
+                    "last_error": stats.last_error,
+                })
+            })
+            .collect();
+
+        let completed: usize = self.stats.iter().map(|s| s.latencies_ms.len()).sum();
+        serde_json::json!({
+            "duration_secs": self.elapsed.as_secs_f64(),
+            "requests": completed,
+            "errors": self.stats.iter().map(|s| s.errors).sum::<u64>(),
+            "throughput": self.throughput(completed),
+            "queries": queries,
+        })
+    }
+
+    fn print_table(&self) {
+        let completed: usize = self.stats.iter().map(|s| s.latencies_ms.len()).sum();
+        let errors: u64 = self.stats.iter().map(|s| s.errors).sum();
+
+        println!("{}", "Load test:".cyan().bold());
+        println!("  Duration: {:.3} seconds", self.elapsed.as_secs_f64());
+        println!("  Requests: {completed} ({errors} errors)");
+        println!(
+            "  Throughput: {:.1} requests/second",
+            self.throughput(completed)
+        );
+        println!();
+
+        let width = self.names.iter().map(|n| n.len()).max().unwrap_or(0).max(5);
+        println!(
+            "{}",
+            format!(
+                "{:<width$} {:>9} {:>7} {:>9} {:>9} {:>9} {:>9}",
+                "Query", "Requests", "Errors", "Mean ms", "p50 ms", "p99 ms", "Max ms"
+            )
+            .cyan()
+            .bold()
+        );
+        for (name, stats) in self.names.iter().zip(&self.stats) {
+            let mut latencies = stats.latencies_ms.clone();
+            let cells = LatencySummary::from_samples(&mut latencies).map_or_else(
+                || vec!["-".to_string(); 4],
+                |l| {
+                    [l.mean, l.p50, l.p99, l.max]
+                        .iter()
+                        .map(|v| format!("{v:.3}"))
+                        .collect()
+                },
+            );
+            println!(
+                "{:<width$} {:>9} {:>7} {:>9} {:>9} {:>9} {:>9}",
+                name,
+                stats.latencies_ms.len(),
+                stats.errors,
+                cells[0],
+                cells[1],
+                cells[2],
+                cells[3]
+            );
+        }
+
+        for (name, stats) in self.names.iter().zip(&self.stats) {
+            if let Some(error) = &stats.last_error {
+                eprintln!("{} {name}: {error}", "Last error".red());
+            }
+        }
+    }
+}
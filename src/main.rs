@@ -1,20 +1,46 @@
 use anyhow::Result;
-use clap::Parser;
 
+mod bench;
 mod cli;
 mod client;
+mod columnar;
 mod commands;
 mod completion;
+mod config;
+mod copy;
+mod cypher;
+mod dot;
+mod dump;
+mod editor;
+mod export;
+mod format;
+mod graphml;
+mod import;
 mod interactive;
+mod load;
+mod output;
+mod pager;
+mod params;
+mod progress;
+mod repeat;
+mod script;
+mod table;
+mod theme;
 
 #[cfg(test)]
 mod tests;
 
 use cli::Cli;
 use client::{ConnectionConfig, FalkorCli};
+use format::FormatOptions;
+use output::OutputFile;
+use repeat::RepeatOptions;
+use std::collections::BTreeMap;
 
 fn main() -> Result<()> {
-    let cli = Cli::parse();
+    let cli = Cli::try_parse_checked(std::env::args_os()).unwrap_or_else(|e| e.exit());
+    cli.color.apply();
+    let settings = config::load_config()?;
 
     let config = ConnectionConfig {
         hostname: &cli.hostname,
@@ -25,25 +51,48 @@ fn main() -> Result<()> {
     };
 
     let mut falkor_cli = FalkorCli::new(&config, cli.format, cli.quiet, cli.raw)?;
+    falkor_cli.pager = !cli.no_pager;
+    falkor_cli.format_options = FormatOptions {
+        display_property: cli.display_property,
+        html_style: cli.html_style,
+        ndjson_stats: cli.ndjson_stats,
+        stats: cli.stats,
+        statistics_to_stderr: cli.output.is_some(),
+        theme: settings.theme,
+        border: cli.border,
+        overflow: cli.overflow,
+        max_width: None,
+        property_limit: (cli.property_limit > 0).then_some(cli.property_limit),
+    };
+    if let Some(path) = &cli.output {
+        falkor_cli.output_file = Some(OutputFile::create_atomic(path)?);
+    }
+    falkor_cli.repeat = RepeatOptions::from_args(cli.repeat, cli.interval, cli.changes_only)?;
 
     if let Some(graph) = cli.graph {
         falkor_cli.set_graph(graph);
     }
 
-    // Handle eval mode
-    if let Some(command) = cli.eval {
-        let graph_name = falkor_cli.get_graph_name(None)?;
-        return falkor_cli.execute_query(&graph_name, &command, false);
-    }
-
-    // Handle file mode
-    if let Some(_file_path) = cli.file {
-        return Err(anyhow::anyhow!("File mode not yet implemented"));
-    }
+    let outcome = if let Some(command) = cli.eval {
+        // Handle eval mode
+        falkor_cli.get_graph_name(None).and_then(|graph_name| {
+            falkor_cli.execute_query_repeated(&graph_name, &command, &BTreeMap::new(), false)
+        })
+    } else if let Some(file_path) = cli.file {
+        // Handle file mode
+        falkor_cli.run_script(&file_path)
+    } else {
+        // Handle subcommands
+        match cli.command {
+            Some(command) => falkor_cli.handle_command(command),
+            None => falkor_cli.interactive_mode(),
+        }
+    };
+    outcome?;
 
-    // Handle subcommands
-    match cli.command {
-        Some(command) => falkor_cli.handle_command(command),
-        None => falkor_cli.interactive_mode(),
+    // The -o file only replaces its target once everything succeeded
+    if let Some(file) = falkor_cli.output_file.take() {
+        file.finish()?;
     }
+    Ok(())
 }
@@ -0,0 +1,270 @@
+use anyhow::{Context, Result};
+use flate2::write::GzEncoder;
+use std::collections::hash_map::RandomState;
+use std::fs::{self, File, OpenOptions};
+use std::hash::{BuildHasher, Hasher};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Compression applied to an output file, chosen by its extension.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Compression {
+    None,
+    Gzip,
+    Zstd,
+}
+
+impl Compression {
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("gz") => Self::Gzip,
+            Some("zst" | "zstd") => Self::Zstd,
+            _ => Self::None,
+        }
+    }
+}
+
+/// Writer of an output file, compressing when the file name asks for it.
+pub enum Encoder {
+    Plain(BufWriter<File>),
+    Gzip(GzEncoder<BufWriter<File>>),
+    Zstd(zstd::Encoder<'static, BufWriter<File>>),
+}
+
+impl Encoder {
+    fn new(file: File, compression: Compression) -> Result<Self> {
+        let file = BufWriter::new(file);
+        Ok(match compression {
+            Compression::None => Self::Plain(file),
+            Compression::Gzip => Self::Gzip(GzEncoder::new(file, flate2::Compression::default())),
+            Compression::Zstd => Self::Zstd(zstd::Encoder::new(file, 0)?),
+        })
+    }
+
+    /// Writes the compression trailer, if any, and flushes the file.
+    fn finish(self) -> io::Result<()> {
+        let mut file = match self {
+            Self::Plain(file) => file,
+            Self::Gzip(encoder) => encoder.finish()?,
+            Self::Zstd(encoder) => encoder.finish()?,
+        };
+        file.flush()
+    }
+}
+
+impl Write for Encoder {
+    fn write(&mut self, bytes: &[u8]) -> io::Result<usize> {
+        match self {
+            Self::Plain(file) => file.write(bytes),
+            Self::Gzip(encoder) => encoder.write(bytes),
+            Self::Zstd(encoder) => encoder.write(bytes),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Self::Plain(file) => file.flush(),
+            Self::Gzip(encoder) => encoder.flush(),
+            Self::Zstd(encoder) => encoder.flush(),
+        }
+    }
+}
+
+/// File receiving query results, from `-o` or `\o`.
+///
+/// With `-o` the results are written to a temporary file next to the target,
+/// which replaces the target in [`OutputFile::finish`]. A run that fails
+/// leaves the previous file untouched and removes the temporary one.
+pub struct OutputFile {
+    encoder: Mutex<Option<Encoder>>,
+    /// File being written
+    path: PathBuf,
+    /// Where `path` is moved once finished, for atomic output
+    target: Option<PathBuf>,
+}
+
+impl OutputFile {
+    /// Creates (or truncates) a file that is written in place, as `\o` does.
+    pub fn create(path: &str) -> Result<Self> {
+        let path = PathBuf::from(path);
+        let file = File::create(&path)
+            .with_context(|| format!("Failed to open output file '{}'", path.display()))?;
+        Ok(Self {
+            encoder: Mutex::new(Some(Encoder::new(file, Compression::from_path(&path))?)),
+            path,
+            target: None,
+        })
+    }
+
+    /// Creates a temporary file that replaces `path` when finished.
+    pub fn create_atomic(path: &str) -> Result<Self> {
+        let target = PathBuf::from(path);
+        let name = target
+            .file_name()
+            .ok_or_else(|| anyhow::anyhow!("Output path '{path}' is not a file"))?
+            .to_string_lossy();
+        let temp = target.with_file_name(format!(".{name}.{}.tmp", std::process::id()));
+        let file = File::create(&temp)
+            .with_context(|| format!("Failed to create '{}'", temp.display()))?;
+        Ok(Self {
+            encoder: Mutex::new(Some(Encoder::new(file, Compression::from_path(&target))?)),
+            path: temp,
+            target: Some(target),
+        })
+    }
+
+    /// Hands the file to `write`, then flushes it so each result is on disk
+    /// as soon as it has been written.
+    pub fn write_with<R>(&self, write: impl FnOnce(&mut dyn Write) -> Result<R>) -> Result<R> {
+        let mut encoder = self
+            .encoder
+            .lock()
+            .map_err(|_| anyhow::anyhow!("Output file lock poisoned"))?;
+        let encoder = encoder
+            .as_mut()
+            .ok_or_else(|| anyhow::anyhow!("Output file already closed"))?;
+        let result = write(encoder)?;
+        encoder.flush()?;
+        Ok(result)
+    }
+
+    /// Completes the file and, for atomic output, moves it into place.
+    pub fn finish(mut self) -> Result<()> {
+        let encoder = self
+            .encoder
+            .get_mut()
+            .map_err(|_| anyhow::anyhow!("Output file lock poisoned"))?
+            .take();
+        if let Some(encoder) = encoder {
+            encoder
+                .finish()
+                .with_context(|| format!("Failed to write '{}'", self.path.display()))?;
+        }
+        if let Some(target) = self.target.take() {
+            fs::rename(&self.path, &target)
+                .with_context(|| format!("Failed to replace '{}'", target.display()))?;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for OutputFile {
+    fn drop(&mut self) {
+        // Only reached without `finish` for atomic output when the run failed
+        if self.target.is_some() {
+            let _ = fs::remove_file(&self.path);
+        }
+    }
+}
+
+/// Creates a new file named `{prefix}-{random}{suffix}` in the system temp
+/// directory, readable only by the current user. The name is unpredictable
+/// and the file is opened with `create_new`, so an existing file or symlink
+/// planted in a shared temp directory is never followed.
+pub fn create_temp_file(prefix: &str, suffix: &str) -> Result<(PathBuf, File)> {
+    let dir = std::env::temp_dir();
+    let mut attempts = 0;
+    loop {
+        // RandomState is seeded from the OS on creation
+        let mut hasher = RandomState::new().build_hasher();
+        hasher.write_u32(std::process::id());
+        hasher.write_u128(
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_nanos()),
+        );
+        let path = dir.join(format!("{prefix}-{:016x}{suffix}", hasher.finish()));
+
+        let mut options = OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+        match options.open(&path) {
+            Ok(file) => return Ok((path, file)),
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists && attempts < 16 => attempts += 1,
+            Err(e) => {
+                return Err(e)
+                    .with_context(|| format!("Failed to create temp file '{}'", path.display()))
+            }
+        }
+    }
+}
+
+/// Opens the file used by `\log` in append mode so successive sessions
+/// accumulate in one place.
+pub fn open_log_file(path: &str) -> Result<File> {
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context(|| format!("Failed to open log file '{path}'"))
+}
+
+/// Appends a statement and what it produced to the query log.
+pub fn log_statement(mut log: &File, statement: &str, output: &[u8]) -> Result<()> {
+    writeln!(log, "-- {}", timestamp())?;
+    writeln!(log, "{}", statement.trim())?;
+    log.write_all(&strip_ansi(output))?;
+    writeln!(log)?;
+    log.flush()?;
+    Ok(())
+}
+
+/// Removes ANSI escape sequences so colored output can be written to files.
+pub fn strip_ansi(input: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(input.len());
+    let mut bytes = input.iter().copied().peekable();
+
+    while let Some(b) = bytes.next() {
+        if b == 0x1b && bytes.peek() == Some(&b'[') {
+            bytes.next();
+            // Skip parameters until the final byte of the CSI sequence
+            for c in bytes.by_ref() {
+                if (0x40..=0x7e).contains(&c) {
+                    break;
+                }
+            }
+        } else {
+            out.push(b);
+        }
+    }
+
+    out
+}
+
+/// Current UTC time in RFC 3339 format, e.g. `2024-05-01T12:30:00Z`.
+pub fn timestamp() -> String {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    format_unix_time(secs)
+}
+
+/// Formats seconds since the Unix epoch as an RFC 3339 UTC timestamp.
+pub fn format_unix_time(secs: u64) -> String {
+    let days = secs / 86_400;
+    let rem = secs % 86_400;
+    let (year, month, day) = civil_from_days(days);
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        rem / 3600,
+        (rem % 3600) / 60,
+        rem % 60
+    )
+}
+
+// Howard Hinnant's days-to-civil algorithm, restricted to dates after 1970
+const fn civil_from_days(days: u64) -> (u64, u64, u64) {
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + (month <= 2) as u64;
+    (year, month, day)
+}
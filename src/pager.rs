@@ -0,0 +1,76 @@
+use anyhow::Result;
+use std::env;
+use std::io::{self, IsTerminal, Write};
+use std::process::{Command, Stdio};
+
+/// Pager used when `$PAGER` is not set. `-R` keeps ANSI colors, `-F` quits
+/// immediately if the output fits on one screen and `-X` leaves it on screen.
+const DEFAULT_PAGER: &str = "less -SRFX";
+
+/// Writes `output` to stdout, piping it through the pager when paging is
+/// enabled, stdout is a terminal and the output is taller than the terminal.
+pub fn write_paged(output: &[u8], enabled: bool) -> Result<()> {
+    if enabled && io::stdout().is_terminal() {
+        let height = terminal_size::terminal_size().map(|(_, h)| usize::from(h.0));
+        if exceeds_height(output, height) && spawn_pager(output).is_ok() {
+            return Ok(());
+        }
+    }
+
+    let mut stdout = io::stdout().lock();
+    stdout.write_all(output)?;
+    stdout.flush()?;
+    Ok(())
+}
+
+/// Width of the terminal stdout is attached to, or `None` when stdout is
+/// not a terminal.
+pub fn terminal_width() -> Option<usize> {
+    if !io::stdout().is_terminal() {
+        return None;
+    }
+    terminal_size::terminal_size().map(|(w, _)| usize::from(w.0))
+}
+
+/// Returns true when `output` needs more lines than the terminal has rows.
+/// One row is reserved for the prompt shown after the output.
+pub fn exceeds_height(output: &[u8], height: Option<usize>) -> bool {
+    height.is_some_and(|rows| {
+        let lines = output.iter().filter(|&&b| b == b'\n').count();
+        lines >= rows
+    })
+}
+
+/// Resolves the pager command from `$PAGER`, falling back to [`DEFAULT_PAGER`].
+/// An empty `$PAGER` disables paging.
+fn pager_command() -> Option<String> {
+    match env::var("PAGER") {
+        Ok(pager) if pager.trim().is_empty() => None,
+        Ok(pager) => Some(pager),
+        Err(_) => Some(DEFAULT_PAGER.to_string()),
+    }
+}
+
+fn spawn_pager(output: &[u8]) -> Result<()> {
+    let command = pager_command().ok_or_else(|| anyhow::anyhow!("Pager disabled"))?;
+    let mut parts = command.split_whitespace();
+    let program = parts
+        .next()
+        .ok_or_else(|| anyhow::anyhow!("Empty pager command"))?;
+
+    let mut child = Command::new(program)
+        .args(parts)
+        .stdin(Stdio::piped())
+        .spawn()?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        // The user may quit the pager before reading everything
+        match stdin.write_all(output) {
+            Err(e) if e.kind() != io::ErrorKind::BrokenPipe => return Err(e.into()),
+            _ => {}
+        }
+    }
+
+    child.wait()?;
+    Ok(())
+}
@@ -0,0 +1,182 @@
+use anyhow::Result;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+
+/// Renders a JSON value as a Cypher literal, suitable for `CYPHER name=value`
+/// parameter prefixes.
+pub fn json_to_cypher(value: &Value) -> String {
+    match value {
+        Value::Null => "null".to_string(),
+        Value::Bool(b) => b.to_string(),
+        Value::Number(n) => n.to_string(),
+        Value::String(s) => Value::String(s.clone()).to_string(),
+        Value::Array(items) => {
+            let items: Vec<String> = items.iter().map(json_to_cypher).collect();
+            format!("[{}]", items.join(", "))
+        }
+        Value::Object(map) => {
+            let entries: Vec<String> = map
+                .iter()
+                .map(|(key, value)| format!("{}: {}", cypher_key(key), json_to_cypher(value)))
+                .collect();
+            format!("{{{}}}", entries.join(", "))
+        }
+    }
+}
+
+/// Map keys and names that are not plain identifiers need backtick quoting.
+pub fn cypher_key(key: &str) -> String {
+    let mut chars = key.chars();
+    let is_identifier = chars.next().is_some_and(|c| c.is_alphabetic() || c == '_')
+        && chars.all(|c| c.is_alphanumeric() || c == '_');
+    if is_identifier {
+        key.to_string()
+    } else {
+        format!("`{}`", key.replace('`', "``"))
+    }
+}
+
+/// Returns the names of all `$parameters` referenced by a query, ignoring
+/// string literals and comments.
+pub fn referenced_parameters(query: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    let mut quote: Option<char> = None;
+    let mut chars = query.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some(q), _) if c == q => quote = None,
+            (Some(_), '\\') => {
+                chars.next();
+            }
+            (Some(_), _) => {}
+            (None, '\'' | '"' | '`') => quote = Some(c),
+            (None, '/') if chars.peek() == Some(&'/') => {
+                while chars.next_if(|&next| next != '\n').is_some() {}
+            }
+            (None, '/') if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut previous = '\0';
+                for next in chars.by_ref() {
+                    if previous == '*' && next == '/' {
+                        break;
+                    }
+                    previous = next;
+                }
+            }
+            (None, '$') => {
+                let mut name = String::new();
+                while let Some(next) = chars.next_if(|&n| n.is_alphanumeric() || n == '_') {
+                    name.push(next);
+                }
+                if !name.is_empty() && !names.contains(&name) {
+                    names.push(name);
+                }
+            }
+            (None, _) => {}
+        }
+    }
+
+    names
+}
+
+/// Returns the names a query defines itself in a leading
+/// `CYPHER name=value ...` prefix. These need no binding.
+pub fn inline_parameters(query: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    let rest = query.trim_start();
+    let Some(prefix) = rest.get(..6) else {
+        return names;
+    };
+    if !prefix.eq_ignore_ascii_case("CYPHER") {
+        return names;
+    }
+    let mut chars = rest[6..].chars().peekable();
+    if !chars.peek().is_some_and(|c| c.is_whitespace()) {
+        return names;
+    }
+
+    loop {
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+        let mut name = String::new();
+        while let Some(next) = chars.next_if(|&n| n.is_alphanumeric() || n == '_') {
+            name.push(next);
+        }
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+        if name.is_empty() || chars.next_if_eq(&'=').is_none() {
+            return names;
+        }
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+
+        // The value ends at the first whitespace outside quotes and brackets
+        let mut quote: Option<char> = None;
+        let mut depth = 0usize;
+        while let Some(&c) = chars.peek() {
+            match (quote, c) {
+                (Some(q), _) if c == q => quote = None,
+                (Some(_), '\\') => {
+                    chars.next();
+                }
+                (Some(_), _) => {}
+                (None, '\'' | '"') => quote = Some(c),
+                (None, '[' | '{' | '(') => depth += 1,
+                (None, ']' | '}' | ')') => depth = depth.saturating_sub(1),
+                (None, c) if c.is_whitespace() && depth == 0 => break,
+                (None, _) => {}
+            }
+            chars.next();
+        }
+        names.push(name);
+    }
+}
+
+/// Parses a `--params` JSON object into parameter values.
+pub fn parse_params_object(json: &str) -> Result<BTreeMap<String, Value>> {
+    match serde_json::from_str(json)? {
+        Value::Object(map) => Ok(map.into_iter().collect()),
+        _ => Err(anyhow::anyhow!("Parameters must be a JSON object")),
+    }
+}
+
+/// Collects the parameters a query references, preferring `explicit` values
+/// over `session` ones, and fails if any are undefined. Names set in the
+/// query's own `CYPHER` prefix are left to the server; binding others as well
+/// would add a second prefix, so mixing the two is an error.
+pub fn bind_parameters(
+    query: &str,
+    explicit: &BTreeMap<String, Value>,
+    session: &BTreeMap<String, Value>,
+) -> Result<HashMap<String, String>> {
+    let mut bound = HashMap::new();
+    let mut missing = Vec::new();
+    let inline = inline_parameters(query);
+
+    for name in referenced_parameters(query) {
+        if inline.contains(&name) {
+            continue;
+        }
+        match explicit.get(&name).or_else(|| session.get(&name)) {
+            Some(value) => {
+                bound.insert(name, json_to_cypher(value));
+            }
+            None => missing.push(format!("${name}")),
+        }
+    }
+
+    if !missing.is_empty() {
+        return Err(anyhow::anyhow!(
+            "Undefined parameter(s): {}. Set them with \\param in interactive mode or --params",
+            missing.join(", ")
+        ));
+    }
+    if !inline.is_empty() && !bound.is_empty() {
+        let mut names: Vec<String> = bound.keys().map(|name| format!("${name}")).collect();
+        names.sort();
+        return Err(anyhow::anyhow!(
+            "The query's CYPHER prefix sets parameters, so {} cannot be bound as well. \
+             Set all of them in the prefix, or none",
+            names.join(", ")
+        ));
+    }
+    Ok(bound)
+}
@@ -0,0 +1,84 @@
+use std::io::{self, IsTerminal, Write};
+use std::time::{Duration, Instant};
+
+const BAR_WIDTH: usize = 30;
+const REDRAW_INTERVAL: Duration = Duration::from_millis(100);
+
+/// A single-line progress display on stderr. Nothing is drawn when stderr is
+/// not a terminal, so logs stay clean.
+pub struct Progress {
+    label: String,
+    total: Option<u64>,
+    current: u64,
+    enabled: bool,
+    last_draw: Option<Instant>,
+}
+
+impl Progress {
+    /// Creates a progress display; `total` is the expected amount of work,
+    /// when known.
+    pub fn new(label: &str, total: Option<u64>) -> Self {
+        Self {
+            label: label.to_string(),
+            total,
+            current: 0,
+            enabled: io::stderr().is_terminal(),
+            last_draw: None,
+        }
+    }
+
+    pub fn set(&mut self, current: u64) {
+        self.current = current;
+        if self
+            .last_draw
+            .is_none_or(|last| last.elapsed() >= REDRAW_INTERVAL)
+        {
+            self.draw();
+        }
+    }
+
+    /// Draws the final state and moves to the next line.
+    pub fn finish(&mut self) {
+        if let Some(total) = self.total {
+            self.current = total;
+        }
+        self.draw();
+        if self.enabled {
+            eprintln!();
+        }
+    }
+
+    fn draw(&mut self) {
+        self.last_draw = Some(Instant::now());
+        if !self.enabled {
+            return;
+        }
+        let line = render(&self.label, self.current, self.total);
+        let mut stderr = io::stderr().lock();
+        let _ = write!(stderr, "\r{line}");
+        let _ = stderr.flush();
+    }
+}
+
+/// Renders a progress line, e.g. `Person [=====>    ] 50%`, or a plain
+/// counter when the total is unknown.
+pub fn render(label: &str, current: u64, total: Option<u64>) -> String {
+    match total {
+        Some(total) if total > 0 => {
+            let current = current.min(total);
+            #[allow(clippy::cast_possible_truncation)]
+            let filled = (u128::from(current) * BAR_WIDTH as u128 / u128::from(total)) as usize;
+            let bar = if filled >= BAR_WIDTH {
+                "=".repeat(BAR_WIDTH)
+            } else {
+                format!(
+                    "{}>{}",
+                    "=".repeat(filled),
+                    " ".repeat(BAR_WIDTH - filled - 1)
+                )
+            };
+            format!("{label} [{bar}] {:>3}%", current * 100 / total)
+        }
+        _ => format!("{label} {current}"),
+    }
+}
@@ -0,0 +1,102 @@
+use anyhow::Result;
+use falkordb::{FalkorValue, QueryResult};
+use std::collections::BTreeMap;
+use std::thread;
+use std::time::Duration;
+
+use crate::client::FalkorCli;
+
+/// How many times to run a query and how to report the results, set by
+/// `--repeat`, `--interval` and `--changes-only`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RepeatOptions {
+    /// Number of runs, or `None` to run until interrupted
+    pub count: Option<u64>,
+    pub interval: Duration,
+    /// Only print results that differ from the previous run
+    pub changes_only: bool,
+}
+
+impl Default for RepeatOptions {
+    fn default() -> Self {
+        Self {
+            count: Some(1),
+            interval: Duration::ZERO,
+            changes_only: false,
+        }
+    }
+}
+
+impl RepeatOptions {
+    /// Builds options from the raw command-line values, where a count of `-1`
+    /// means forever.
+    pub fn from_args(count: i64, interval: f64, changes_only: bool) -> Result<Self> {
+        let count = match count {
+            -1 => None,
+            n if n > 0 => Some(n.unsigned_abs()),
+            _ => return Err(anyhow::anyhow!("--repeat must be a positive number or -1")),
+        };
+        if !interval.is_finite() || interval < 0.0 {
+            return Err(anyhow::anyhow!("--interval must be a non-negative number"));
+        }
+
+        Ok(Self {
+            count,
+            interval: Duration::from_secs_f64(interval),
+            changes_only,
+        })
+    }
+}
+
+impl FalkorCli {
+    /// Executes a query according to [`RepeatOptions`]. A single run behaves
+    /// exactly like [`FalkorCli::execute_query_with_params`].
+    pub fn execute_query_repeated(
+        &mut self,
+        graph_name: &str,
+        query: &str,
+        params: &BTreeMap<String, serde_json::Value>,
+        readonly: bool,
+    ) -> Result<()> {
+        let options = self.repeat;
+        if options.count == Some(1) {
+            return self.execute_query_with_params(graph_name, query, params, readonly);
+        }
+
+        // Paging every run would block the loop
+        self.pager = false;
+
+        let mut previous: Option<Vec<Vec<FalkorValue>>> = None;
+        let mut run = 0;
+        loop {
+            if options.changes_only {
+                self.run_query(graph_name, query, params, readonly, |result, elapsed| {
+                    let QueryResult {
+                        header,
+                        data,
+                        stats,
+                    } = result;
+                    let rows: Vec<Vec<FalkorValue>> = data.collect();
+                    if previous.as_ref() == Some(&rows) {
+                        return Ok(());
+                    }
+                    let result = QueryResult {
+                        header,
+                        data: rows.clone().into_iter(),
+                        stats,
+                    };
+                    previous = Some(rows);
+                    self.emit_query_result(query, result, Some(elapsed))
+                })?;
+            } else {
+                self.execute_query_with_params(graph_name, query, params, readonly)?;
+            }
+
+            run += 1;
+            if options.count.is_some_and(|count| run >= count) {
+                return Ok(());
+            }
+            thread::sleep(options.interval);
+        }
+    }
+}
@@ -0,0 +1,188 @@
+use anyhow::{Context, Result};
+use colored::Colorize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::client::FalkorCli;
+
+/// Maximum nesting of `\i` includes, guarding against scripts that include themselves.
+const MAX_INCLUDE_DEPTH: usize = 16;
+
+/// A single statement read from a script, with the line it starts on.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Statement {
+    pub line: usize,
+    pub text: String,
+}
+
+/// Splits a script into statements.
+///
+/// Cypher statements are terminated by `;` (outside of quotes and comments) or
+/// by the end of the file. Lines starting with `\` are meta-commands and end at
+/// the end of the line. `//` and `/* */` comments are dropped.
+pub fn split_statements(script: &str) -> Vec<Statement> {
+    let mut statements = Vec::new();
+    let mut current = String::new();
+    let mut start_line = 1;
+    let mut line = 1;
+    let mut quote: Option<char> = None;
+    let mut chars = script.chars().peekable();
+
+    let mut flush = |current: &mut String, start_line: usize| {
+        let text = current.trim();
+        if !text.is_empty() {
+            statements.push(Statement {
+                line: start_line,
+                text: text.to_string(),
+            });
+        }
+        current.clear();
+    };
+
+    while let Some(c) = chars.next() {
+        if current.trim().is_empty() && quote.is_none() {
+            if !c.is_whitespace() {
+                start_line = line;
+            }
+            if c == '\\' {
+                // Meta-command: runs to the end of the line
+                current.push(c);
+                while let Some(&next) = chars.peek() {
+                    if next == '\n' {
+                        break;
+                    }
+                    current.push(next);
+                    chars.next();
+                }
+                flush(&mut current, start_line);
+                continue;
+            }
+        }
+
+        match (quote, c) {
+            (Some(q), _) if c == q => {
+                quote = None;
+                current.push(c);
+            }
+            (Some(_), '\\') => {
+                current.push(c);
+                if let Some(escaped) = chars.next() {
+                    if escaped == '\n' {
+                        line += 1;
+                    }
+                    current.push(escaped);
+                }
+            }
+            (Some(_), _) => {
+                if c == '\n' {
+                    line += 1;
+                }
+                current.push(c);
+            }
+            (None, '\'' | '"' | '`') => {
+                quote = Some(c);
+                current.push(c);
+            }
+            (None, '/') if chars.peek() == Some(&'/') => {
+                while let Some(&next) = chars.peek() {
+                    if next == '\n' {
+                        break;
+                    }
+                    chars.next();
+                }
+            }
+            (None, '/') if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut prev = '\0';
+                for next in chars.by_ref() {
+                    if next == '\n' {
+                        line += 1;
+                    }
+                    if prev == '*' && next == '/' {
+                        break;
+                    }
+                    prev = next;
+                }
+                current.push(' ');
+            }
+            (None, ';') => flush(&mut current, start_line),
+            (None, _) => {
+                if c == '\n' {
+                    line += 1;
+                }
+                current.push(c);
+            }
+        }
+    }
+
+    flush(&mut current, start_line);
+    statements
+}
+
+/// Where [`run_script`] sends statements, and the stack of scripts it is
+/// running, innermost last. `\i` statements call back into [`run_script`].
+pub trait ScriptHost {
+    fn include_stack(&mut self) -> &mut Vec<PathBuf>;
+    fn run_statement(&mut self, statement: &str) -> Result<()>;
+}
+
+/// Runs every statement of a script file.
+///
+/// Relative paths are resolved against the directory of the including
+/// script, or the working directory at the top level. Execution stops at
+/// the first failing statement.
+pub fn run_script(host: &mut impl ScriptHost, path: &str) -> Result<()> {
+    let stack = host.include_stack();
+    let resolved = resolve_include(stack.last().map(PathBuf::as_path), path);
+
+    if stack.len() >= MAX_INCLUDE_DEPTH {
+        return Err(anyhow::anyhow!(
+            "Script includes nested deeper than {MAX_INCLUDE_DEPTH} levels at '{}'",
+            resolved.display()
+        ));
+    }
+
+    let script = fs::read_to_string(&resolved)
+        .with_context(|| format!("Failed to read script '{}'", resolved.display()))?;
+
+    stack.push(resolved.clone());
+    let result = split_statements(&script)
+        .into_iter()
+        .try_for_each(|statement| {
+            host.run_statement(&statement.text)
+                .map_err(|e| anyhow::anyhow!("{}:{}: {e}", resolved.display(), statement.line))
+        });
+    host.include_stack().pop();
+    result
+}
+
+impl ScriptHost for FalkorCli {
+    fn include_stack(&mut self) -> &mut Vec<PathBuf> {
+        &mut self.include_stack
+    }
+
+    fn run_statement(&mut self, statement: &str) -> Result<()> {
+        if self.echo {
+            println!("{}", statement.dimmed());
+        }
+        self.handle_interactive_command(statement)
+    }
+}
+
+impl FalkorCli {
+    /// Runs a script file against the current graph; see [`run_script`].
+    pub fn run_script(&mut self, path: &str) -> Result<()> {
+        run_script(self, path)
+    }
+}
+
+/// Resolves an included script path against the directory of the script
+/// including it. Absolute paths, and paths given outside any script, are
+/// used as they are.
+pub fn resolve_include(including: Option<&Path>, path: &str) -> PathBuf {
+    let path = Path::new(path);
+    match including.and_then(Path::parent) {
+        Some(dir) if path.is_relative() => dir.join(path),
+        _ => path.to_path_buf(),
+    }
+}
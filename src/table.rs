@@ -0,0 +1,411 @@
+use anyhow::Result;
+use clap::ValueEnum;
+use falkordb::FalkorValue;
+use std::io::Write;
+use std::ops::Range;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+use crate::cypher::display_value;
+use crate::format::{write_statistics, FormatOptions, OutputFormatter, ResultSummary, StatsMode};
+use crate::params::cypher_key;
+use crate::theme::{Style, Theme};
+
+/// Columns are not narrowed below this many terminal cells (or their own
+/// width, if smaller) when fitting a table to the terminal.
+pub const MIN_COLUMN_WIDTH: usize = 5;
+
+/// Characters used to draw table borders.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum Border {
+    /// `+`, `-` and `|`
+    #[default]
+    Ascii,
+    /// Box-drawing characters
+    Unicode,
+}
+
+/// Left, middle and right corners of a horizontal rule.
+type Corners = [char; 3];
+
+impl Border {
+    const fn horizontal(self) -> char {
+        match self {
+            Self::Ascii => '-',
+            Self::Unicode => '─',
+        }
+    }
+
+    const fn vertical(self) -> char {
+        match self {
+            Self::Ascii => '|',
+            Self::Unicode => '│',
+        }
+    }
+
+    const fn top(self) -> Corners {
+        match self {
+            Self::Ascii => ['+', '+', '+'],
+            Self::Unicode => ['┌', '┬', '┐'],
+        }
+    }
+
+    const fn middle(self) -> Corners {
+        match self {
+            Self::Ascii => ['+', '+', '+'],
+            Self::Unicode => ['├', '┼', '┤'],
+        }
+    }
+
+    const fn bottom(self) -> Corners {
+        match self {
+            Self::Ascii => ['+', '+', '+'],
+            Self::Unicode => ['└', '┴', '┘'],
+        }
+    }
+}
+
+/// What happens to cells wider than their column.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum Overflow {
+    /// Cut the cell and end it with `…`
+    #[default]
+    Truncate,
+    /// Continue the cell on the following lines
+    Wrap,
+}
+
+/// A rendered table cell.
+struct Cell {
+    text: String,
+    style: Option<Style>,
+    /// Bytes of `text` painted with `style`
+    painted: Range<usize>,
+    /// Numbers are right-aligned
+    numeric: bool,
+}
+
+impl Cell {
+    fn new(text: String, style: Option<Style>, numeric: bool, overflow: Overflow) -> Self {
+        // Line breaks are kept only where the cell may span several lines
+        let text = match overflow {
+            Overflow::Truncate => text.replace(['\n', '\r', '\t'], " "),
+            Overflow::Wrap => text.replace(['\r', '\t'], " "),
+        };
+        Self {
+            painted: 0..text.len(),
+            text,
+            style,
+            numeric,
+        }
+    }
+
+    /// Paints only part of the text, e.g. the labels of a node.
+    fn painted(mut self, range: Range<usize>) -> Self {
+        self.painted = range;
+        self
+    }
+
+    fn width(&self) -> usize {
+        self.text
+            .lines()
+            .map(UnicodeWidthStr::width)
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn lines(&self, width: usize, overflow: Overflow) -> Vec<String> {
+        match overflow {
+            Overflow::Truncate => vec![truncate(&self.text, width)],
+            Overflow::Wrap => wrap(&self.text, width),
+        }
+    }
+
+    /// Applies the style to the painted part of a line starting `offset`
+    /// bytes into the text.
+    fn paint(&self, line: &str, offset: usize) -> String {
+        let Some(style) = self.style else {
+            return line.to_string();
+        };
+        let clamp = |position: usize| {
+            let mut position = position.saturating_sub(offset).min(line.len());
+            while !line.is_char_boundary(position) {
+                position -= 1;
+            }
+            position
+        };
+        let (start, end) = (clamp(self.painted.start), clamp(self.painted.end));
+        if start >= end {
+            return line.to_string();
+        }
+        format!(
+            "{}{}{}",
+            &line[..start],
+            style.paint(&line[start..end]),
+            &line[end..]
+        )
+    }
+}
+
+/// Statistics followed by a table whose columns are as wide as their widest
+/// cell, narrowed to fit the terminal when there is one.
+pub struct TableFormatter {
+    stats: StatsMode,
+    theme: Theme,
+    border: Border,
+    overflow: Overflow,
+    max_width: Option<usize>,
+    property_limit: Option<usize>,
+    header: Vec<Cell>,
+    rows: Vec<Vec<Cell>>,
+}
+
+impl TableFormatter {
+    pub fn new(options: &FormatOptions) -> Self {
+        Self {
+            stats: if options.statistics_to_stderr {
+                StatsMode::None
+            } else {
+                options.stats
+            },
+            theme: options.theme.clone(),
+            border: options.border,
+            overflow: options.overflow,
+            max_width: options.max_width,
+            property_limit: options.property_limit,
+            header: Vec::new(),
+            rows: Vec::new(),
+        }
+    }
+
+    fn cell(&self, value: &FalkorValue) -> Cell {
+        let (style, numeric) = match value {
+            FalkorValue::I64(_) | FalkorValue::F64(_) => (None, true),
+            FalkorValue::Node(_) => (Some(self.theme.label), false),
+            FalkorValue::Edge(_) => (Some(self.theme.edge_type), false),
+            FalkorValue::None => (Some(self.theme.null), false),
+            _ => (None, false),
+        };
+        let text = display_value(value, self.property_limit);
+        let cell = Cell::new(text, style, numeric, self.overflow);
+        match label_span(value) {
+            Some(span) => cell.painted(span),
+            None => cell,
+        }
+    }
+
+    fn write_rule(&self, out: &mut dyn Write, widths: &[usize], corners: Corners) -> Result<()> {
+        let [left, middle, right] = corners;
+        let horizontal = self.border.horizontal().to_string();
+        let segments: Vec<String> = widths
+            .iter()
+            .map(|width| horizontal.repeat(width + 2))
+            .collect();
+        writeln!(out, "{left}{}{right}", segments.join(&middle.to_string()))?;
+        Ok(())
+    }
+
+    fn write_row(&self, out: &mut dyn Write, cells: &[Cell], widths: &[usize]) -> Result<()> {
+        let vertical = self.border.vertical();
+        let lines: Vec<Vec<String>> = cells
+            .iter()
+            .zip(widths)
+            .map(|(cell, width)| cell.lines(*width, self.overflow))
+            .collect();
+        let height = lines.iter().map(Vec::len).max().unwrap_or(1);
+
+        let offsets: Vec<Vec<usize>> = cells
+            .iter()
+            .zip(&lines)
+            .map(|(cell, lines)| line_offsets(&cell.text, lines))
+            .collect();
+
+        for line in 0..height {
+            write!(out, "{vertical}")?;
+            for (index, width) in widths.iter().enumerate() {
+                let cell = cells.get(index);
+                let text = lines
+                    .get(index)
+                    .and_then(|lines| lines.get(line))
+                    .map_or("", String::as_str);
+                let padding = " ".repeat(width.saturating_sub(text.width()));
+                let offset = offsets
+                    .get(index)
+                    .and_then(|offsets| offsets.get(line))
+                    .copied()
+                    .unwrap_or(0);
+                let painted = match cell {
+                    Some(cell) if !text.is_empty() => cell.paint(text, offset),
+                    _ => text.to_string(),
+                };
+                if cell.is_some_and(|cell| cell.numeric) {
+                    write!(out, " {padding}{painted} {vertical}")?;
+                } else {
+                    write!(out, " {painted}{padding} {vertical}")?;
+                }
+            }
+            writeln!(out)?;
+        }
+        Ok(())
+    }
+}
+
+impl OutputFormatter for TableFormatter {
+    fn begin(&mut self, summary: &ResultSummary, out: &mut dyn Write) -> Result<()> {
+        write_statistics(summary, self.stats, out)?;
+        self.header = summary
+            .header
+            .iter()
+            .map(|name| Cell::new(name.clone(), Some(self.theme.header), false, self.overflow))
+            .collect();
+        Ok(())
+    }
+
+    fn row(&mut self, row: &[FalkorValue], _out: &mut dyn Write) -> Result<()> {
+        let cells = row.iter().map(|value| self.cell(value)).collect();
+        self.rows.push(cells);
+        Ok(())
+    }
+
+    fn end(&mut self, out: &mut dyn Write) -> Result<()> {
+        if self.header.is_empty() {
+            return Ok(());
+        }
+        let natural: Vec<usize> = (0..self.header.len())
+            .map(|index| {
+                self.rows
+                    .iter()
+                    .filter_map(|row| row.get(index))
+                    .chain([&self.header[index]])
+                    .map(Cell::width)
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+        let widths = fit_widths(&natural, self.max_width);
+
+        self.write_rule(out, &widths, self.border.top())?;
+        self.write_row(out, &self.header, &widths)?;
+        self.write_rule(out, &widths, self.border.middle())?;
+        for row in &self.rows {
+            self.write_row(out, row, &widths)?;
+        }
+        self.write_rule(out, &widths, self.border.bottom())?;
+        self.rows.clear();
+        Ok(())
+    }
+}
+
+/// Narrows the widest columns until the table, borders included, fits in
+/// `max_width` terminal cells. Columns stop at [`MIN_COLUMN_WIDTH`], so a
+/// table with many columns can still be wider than asked.
+pub fn fit_widths(natural: &[usize], max_width: Option<usize>) -> Vec<usize> {
+    let Some(max_width) = max_width else {
+        return natural.to_vec();
+    };
+    let available = max_width.saturating_sub(3 * natural.len() + 1);
+    if natural.iter().sum::<usize>() <= available {
+        return natural.to_vec();
+    }
+
+    // Find the largest width every column can be capped at
+    let mut sorted = natural.to_vec();
+    sorted.sort_unstable();
+    let mut remaining = available;
+    let mut cap = 0;
+    for (index, &width) in sorted.iter().enumerate() {
+        let columns = sorted.len() - index;
+        if width * columns > remaining {
+            cap = remaining / columns;
+            break;
+        }
+        remaining -= width;
+    }
+    let cap = cap.max(MIN_COLUMN_WIDTH);
+    natural.iter().map(|&width| width.min(cap)).collect()
+}
+
+/// The `:Label` part of a node's or the `:TYPE` part of a relationship's
+/// text, which the theme colors. `None` for other values.
+pub fn label_span(value: &FalkorValue) -> Option<Range<usize>> {
+    let length = match value {
+        FalkorValue::Node(node) => node
+            .labels
+            .iter()
+            .map(|label| 1 + cypher_key(label).len())
+            .sum(),
+        FalkorValue::Edge(edge) => 1 + cypher_key(&edge.relationship_type).len(),
+        _ => return None,
+    };
+    // Both start with `(` or `[`
+    Some(1..1 + length)
+}
+
+/// Byte offsets of `lines` in the text they were cut or wrapped from.
+/// Wrapping drops the space or line break a line ended at.
+pub fn line_offsets(text: &str, lines: &[String]) -> Vec<usize> {
+    let mut offsets = Vec::with_capacity(lines.len());
+    let mut position = 0;
+    for line in lines {
+        let rest = text.get(position..).unwrap_or("");
+        if position > 0 && !rest.starts_with(line.as_str()) && rest.starts_with([' ', '\n']) {
+            position += 1;
+        }
+        offsets.push(position);
+        position += line.len();
+    }
+    offsets
+}
+
+/// Cuts `text` to at most `width` terminal cells, ending it with `…` when
+/// anything was cut.
+pub fn truncate(text: &str, width: usize) -> String {
+    if text.width() <= width {
+        return text.to_string();
+    }
+    let mut truncated = String::new();
+    let mut used = 0;
+    for ch in text.chars() {
+        let ch_width = ch.width().unwrap_or(0);
+        if used + ch_width + 1 > width {
+            break;
+        }
+        truncated.push(ch);
+        used += ch_width;
+    }
+    truncated.push('…');
+    truncated
+}
+
+/// Splits `text` into lines of at most `width` terminal cells, breaking at
+/// spaces where possible and inside words otherwise.
+pub fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    for paragraph in text.split('\n') {
+        let mut line = String::new();
+        let mut used = 0;
+        for word in paragraph.split(' ') {
+            let word_width = word.width();
+            if used > 0 && used + 1 + word_width <= width {
+                line.push(' ');
+                line.push_str(word);
+                used += 1 + word_width;
+                continue;
+            }
+            if used > 0 {
+                lines.push(std::mem::take(&mut line));
+                used = 0;
+            }
+            for ch in word.chars() {
+                let ch_width = ch.width().unwrap_or(0);
+                if used > 0 && used + ch_width > width {
+                    lines.push(std::mem::take(&mut line));
+                    used = 0;
+                }
+                line.push(ch);
+                used += ch_width;
+            }
+        }
+        lines.push(line);
+    }
+    lines
+}
@@ -0,0 +1,156 @@
+use clap::ValueEnum;
+use colored::{Color, ColoredString, Colorize};
+use serde::Deserialize;
+use std::io::{self, IsTerminal};
+use std::str::FromStr;
+
+/// When to color output, from `--color`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum ColorChoice {
+    /// Color when stdout is a terminal, honoring `NO_COLOR` and `CLICOLOR_FORCE`
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorChoice {
+    /// Whether output should be colored. In `auto` mode a non-empty
+    /// `NO_COLOR` turns colors off and a `CLICOLOR_FORCE` other than `0`
+    /// turns them on even when stdout is not a terminal.
+    pub fn enabled(self, env: impl Fn(&str) -> Option<String>, terminal: bool) -> bool {
+        match self {
+            Self::Always => true,
+            Self::Never => false,
+            Self::Auto => {
+                if env("NO_COLOR").is_some_and(|value| !value.is_empty()) {
+                    false
+                } else if env("CLICOLOR_FORCE").is_some_and(|value| value != "0") {
+                    true
+                } else {
+                    terminal
+                }
+            }
+        }
+    }
+
+    /// Turns colors on or off for the rest of the process.
+    pub fn apply(self) {
+        let enabled = self.enabled(|name| std::env::var(name).ok(), io::stdout().is_terminal());
+        colored::control::set_override(enabled);
+    }
+}
+
+/// A color with text attributes, written in the config file as
+/// space-separated words, e.g. `bold cyan`, `bright black` or `#ff8800 underline`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub struct Style {
+    color: Option<Color>,
+    bold: bool,
+    dimmed: bool,
+    italic: bool,
+    underline: bool,
+}
+
+impl Style {
+    pub fn paint(self, text: &str) -> ColoredString {
+        let mut painted = text.normal();
+        if let Some(color) = self.color {
+            painted = painted.color(color);
+        }
+        if self.bold {
+            painted = painted.bold();
+        }
+        if self.dimmed {
+            painted = painted.dimmed();
+        }
+        if self.italic {
+            painted = painted.italic();
+        }
+        if self.underline {
+            painted = painted.underline();
+        }
+        painted
+    }
+}
+
+impl FromStr for Style {
+    type Err = String;
+
+    fn from_str(spec: &str) -> Result<Self, Self::Err> {
+        let mut style = Self::default();
+        let mut words = spec.split_whitespace();
+        while let Some(word) = words.next() {
+            match word.to_lowercase().as_str() {
+                "bold" => style.bold = true,
+                "dim" | "dimmed" => style.dimmed = true,
+                "italic" => style.italic = true,
+                "underline" => style.underline = true,
+                "normal" | "default" => {}
+                "bright" => {
+                    let color = words.next().unwrap_or_default();
+                    style.color = Some(
+                        Color::from_str(&format!("bright {color}"))
+                            .map_err(|()| format!("Unknown color 'bright {color}'"))?,
+                    );
+                }
+                hex if hex.starts_with('#') && hex.len() == 7 && hex.is_ascii() => {
+                    let channel = |range| {
+                        u8::from_str_radix(&hex[range], 16)
+                            .map_err(|_| format!("Invalid color '{hex}'"))
+                    };
+                    style.color = Some(Color::TrueColor {
+                        r: channel(1..3)?,
+                        g: channel(3..5)?,
+                        b: channel(5..7)?,
+                    });
+                }
+                name => {
+                    style.color = Some(
+                        Color::from_str(name).map_err(|()| format!("Unknown color '{name}'"))?,
+                    );
+                }
+            }
+        }
+        Ok(style)
+    }
+}
+
+impl TryFrom<String> for Style {
+    type Error = String;
+
+    fn try_from(spec: String) -> Result<Self, Self::Error> {
+        spec.parse()
+    }
+}
+
+/// Colors used for the parts of the output, set under `theme:` in the config
+/// file. Unset entries keep their default.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Theme {
+    /// Column headers in table output
+    pub header: Style,
+    /// Labels of nodes in table cells
+    pub label: Style,
+    /// Types of relationships in table cells
+    pub edge_type: Style,
+    /// Null values in table cells
+    pub null: Style,
+    /// Error messages
+    pub error: Style,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        let style = |spec: &str| spec.parse().unwrap_or_default();
+        Self {
+            header: style("bold cyan"),
+            label: style("yellow"),
+            edge_type: style("magenta"),
+            null: style("bright black"),
+            error: style("red"),
+        }
+    }
+}